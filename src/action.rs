@@ -4,79 +4,670 @@
 // of a Runar service action by automatically generating handler code for
 // parameter extraction, validation, and response formatting.
 
+use crate::utils::{
+    expect_lit_bool, expect_lit_str, expect_positive_int, extract_result_ok_type, has_marker,
+    is_bytes_type, is_cow_str_type, is_primitive_param_type, map_key_value_types,
+    parameter_aliases, strip_markers, vec_element_type, PARAMETER_MARKERS,
+};
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::{format_ident, quote};
-use syn::parse::Parser;
+use quote::{format_ident, quote, quote_spanned};
+use std::collections::HashMap;
+use syn::parse::{Parse, ParseStream};
 use syn::{
-    parse_macro_input, punctuated::Punctuated, token::Comma, FnArg, Ident, ItemFn, Lit, Pat,
-    PatIdent, PatType, ReturnType, Type,
+    parse_macro_input, punctuated::Punctuated, token::Comma, Attribute, Expr, FnArg, Ident, ItemFn,
+    LitBool, LitInt, LitStr, Meta, MetaNameValue, Pat, PatIdent, PatType, ReturnType, Type,
 };
 
+// Define a struct to parse the macro attributes
+#[derive(Default)]
+pub struct ActionImpl {
+    /// Action name, defaults to the function name
+    pub name: Option<String>,
+    /// Action path, defaults to the action name
+    pub path: Option<String>,
+    /// Method run on a successful result before it is serialized
+    pub after: Option<Ident>,
+    /// Free-form key-value annotations from `meta(key = "value", ...)`
+    pub annotations: Vec<(String, String)>,
+    /// Predicate evaluated at init time, the action is only registered when it is true
+    pub enabled_if: Option<Expr>,
+    /// Payload field holding the key used to dedupe retried requests
+    pub idempotency_key: Option<LitStr>,
+    /// Number of idempotency keys remembered before the least recently used is evicted
+    pub idempotency_cache_size: Option<LitInt>,
+    /// JSON schema file, relative to the crate root, the parameters are validated against
+    pub schema: Option<LitStr>,
+    /// Hand the whole payload to the single parameter without deserializing it
+    pub raw_payload: bool,
+    /// Maximum number of invocations of this action running at the same time
+    pub max_concurrency: Option<LitInt>,
+    /// Whether generated log and error messages may quote argument values, `log_args = false`
+    /// keeps them out for actions handling secrets
+    pub log_args: Option<LitBool>,
+    /// Generate a typed `<Action>Params` struct building the action's payload
+    pub params_struct: Option<LitBool>,
+    /// Fields removed from a map-shaped result before it is returned
+    pub redact: Vec<LitStr>,
+    /// Names of the values of a tuple result, which is returned as a map of name to value
+    pub outputs: Vec<Ident>,
+    /// Time the action may run before it is cancelled and the request fails
+    pub timeout_ms: Option<LitInt>,
+    /// Method called when the action times out, to release resources or roll back
+    pub on_timeout: Option<Ident>,
+    /// Cap the action's run time to what is left of the caller's deadline
+    pub respect_deadline: Option<LitBool>,
+    /// Return one page of a `Vec` result, windowed by the payload's `cursor` and `limit`
+    pub paginate: Option<LitBool>,
+    /// Read the parameters by index from a list payload, in declaration order
+    pub positional: Option<LitBool>,
+    /// Type whose `encode`/`decode` functions replace the default `ArcValueType` conversions
+    pub serializer: Option<Type>,
+    /// Topic intermediate results are published to through the `progress` sink, `{name}`
+    /// segments are filled from the parameter of the same name
+    pub progress_topic: Option<LitStr>,
+}
+
+impl ActionImpl {
+    /// Check whether the result is returned one page at a time
+    pub fn paginates(&self) -> bool {
+        self.paginate
+            .as_ref()
+            .is_some_and(|paginate| paginate.value)
+    }
+
+    /// Check whether the parameters are read from a list payload instead of a map
+    pub fn is_positional(&self) -> bool {
+        self.positional
+            .as_ref()
+            .is_some_and(|positional| positional.value)
+    }
+
+    /// Check whether the action stops when the caller's deadline passes
+    pub fn respects_deadline(&self) -> bool {
+        self.respect_deadline
+            .as_ref()
+            .is_some_and(|respect_deadline| respect_deadline.value)
+    }
+
+    /// Check whether argument values must be kept out of generated messages
+    pub fn redacts_args(&self) -> bool {
+        self.log_args
+            .as_ref()
+            .is_some_and(|log_args| !log_args.value)
+    }
+
+    /// Tokens for the error binding and the error detail in generated deserialization errors
+    ///
+    /// With `log_args = false` the error, which can quote the argument value, is discarded.
+    pub fn error_tokens(&self) -> (TokenStream2, TokenStream2) {
+        if self.redacts_args() {
+            (quote! { _ }, quote! { "invalid value" })
+        } else {
+            (quote! { err }, quote! { err })
+        }
+    }
+
+    /// Tokens converting an owned `ArcValueType` expression into `value_type`, through the
+    /// action's serializer when it has one
+    pub fn decode_tokens(&self, value: TokenStream2, value_type: &TokenStream2) -> TokenStream2 {
+        match &self.serializer {
+            Some(serializer) => quote! { <#serializer>::decode::<#value_type>(#value) },
+            None => quote! { #value.as_type::<#value_type>() },
+        }
+    }
+}
+
+impl Parse for ActionImpl {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut action_impl = ActionImpl::default();
+
+        // A leading string literal sets both the name and the path for backward compatibility
+        if input.peek(LitStr) {
+            let name = input.parse::<LitStr>()?.value();
+            action_impl.path = Some(name.clone());
+            action_impl.name = Some(name);
+
+            if input.is_empty() {
+                return Ok(action_impl);
+            }
+            input.parse::<Comma>()?;
+        }
+
+        // Remaining arguments are key = value options
+        let metas = Punctuated::<Meta, Comma>::parse_terminated(input)?;
+        for meta in metas {
+            // meta(key = "value", ...) holds arbitrary annotations
+            if let Meta::List(list) = &meta {
+                if list.path.is_ident("meta") {
+                    let annotations =
+                        list.parse_args_with(Punctuated::<MetaNameValue, Comma>::parse_terminated)?;
+                    for annotation in annotations {
+                        let key = annotation.path.get_ident().ok_or_else(|| {
+                            syn::Error::new_spanned(
+                                &annotation.path,
+                                "Expected a simple annotation key",
+                            )
+                        })?;
+                        let value = expect_lit_str(&annotation.value)?;
+                        action_impl
+                            .annotations
+                            .push((key.to_string(), value.value()));
+                    }
+                    continue;
+                }
+
+                // outputs(name, ...) names the values of a tuple result
+                if list.path.is_ident("outputs") {
+                    let outputs =
+                        list.parse_args_with(Punctuated::<Ident, Comma>::parse_terminated)?;
+                    for (index, output) in outputs.iter().enumerate() {
+                        if outputs.iter().take(index).any(|other| other == output) {
+                            return Err(syn::Error::new_spanned(
+                                output,
+                                format!("Duplicate output name `{}`", output),
+                            ));
+                        }
+                    }
+                    action_impl.outputs = outputs.into_iter().collect();
+                    continue;
+                }
+            }
+
+            // raw_payload is a bare flag
+            if let Meta::Path(path) = &meta {
+                if path.is_ident("raw_payload") {
+                    action_impl.raw_payload = true;
+                    continue;
+                }
+            }
+
+            let Meta::NameValue(name_value) = &meta else {
+                return Err(syn::Error::new_spanned(
+                    &meta,
+                    "Expected a string literal or a key = value action option",
+                ));
+            };
+
+            if name_value.path.is_ident("path") {
+                action_impl.path = Some(expect_lit_str(&name_value.value)?.value());
+            } else if name_value.path.is_ident("name") {
+                action_impl.name = Some(expect_lit_str(&name_value.value)?.value());
+            } else if name_value.path.is_ident("after") {
+                let method = expect_lit_str(&name_value.value)?;
+                action_impl.after = Some(method.parse::<Ident>().map_err(|_| {
+                    syn::Error::new_spanned(&method, "Expected the name of a method on the service")
+                })?);
+            } else if name_value.path.is_ident("enabled_if") {
+                let predicate = expect_lit_str(&name_value.value)?;
+                // A bare method name is called on the service
+                action_impl.enabled_if = Some(match predicate.parse::<Ident>() {
+                    Ok(method) => syn::parse_quote! { self.#method() },
+                    Err(_) => predicate.parse::<Expr>()?,
+                });
+            } else if name_value.path.is_ident("idempotency_key") {
+                action_impl.idempotency_key = Some(expect_lit_str(&name_value.value)?);
+            } else if name_value.path.is_ident("schema") {
+                let schema = expect_lit_str(&name_value.value)?;
+                if !cfg!(feature = "schema_validation") {
+                    return Err(syn::Error::new_spanned(
+                        &schema,
+                        "Schema validation requires the `schema_validation` feature of runar_macros",
+                    ));
+                }
+                action_impl.schema = Some(schema);
+            } else if name_value.path.is_ident("max_concurrency") {
+                action_impl.max_concurrency =
+                    Some(expect_positive_int::<usize>(&name_value.value)?);
+            } else if name_value.path.is_ident("log_args") {
+                action_impl.log_args = Some(expect_lit_bool(&name_value.value, "log_args")?);
+            } else if name_value.path.is_ident("params_struct") {
+                action_impl.params_struct =
+                    Some(expect_lit_bool(&name_value.value, "params_struct")?);
+            } else if name_value.path.is_ident("progress_topic") {
+                let topic = expect_lit_str(&name_value.value)?;
+                if topic.value().trim().is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        &topic,
+                        "progress_topic cannot be empty",
+                    ));
+                }
+                action_impl.progress_topic = Some(topic);
+            } else if name_value.path.is_ident("paginate") {
+                action_impl.paginate = Some(expect_lit_bool(&name_value.value, "paginate")?);
+            } else if name_value.path.is_ident("positional") {
+                action_impl.positional = Some(expect_lit_bool(&name_value.value, "positional")?);
+            } else if name_value.path.is_ident("serializer") {
+                let serializer = expect_lit_str(&name_value.value)?;
+                action_impl.serializer = Some(serializer.parse::<Type>().map_err(|_| {
+                    syn::Error::new_spanned(
+                        &serializer,
+                        "Expected a serializer type, e.g. serializer = \"CompactSerde\"",
+                    )
+                })?);
+            } else if name_value.path.is_ident("respect_deadline") {
+                action_impl.respect_deadline =
+                    Some(expect_lit_bool(&name_value.value, "respect_deadline")?);
+            } else if name_value.path.is_ident("redact") {
+                let Expr::Array(fields) = &name_value.value else {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.value,
+                        "Expected a list of field names, e.g. redact = [\"password\"]",
+                    ));
+                };
+                action_impl.redact = fields
+                    .elems
+                    .iter()
+                    .map(expect_lit_str)
+                    .collect::<syn::Result<Vec<_>>>()?;
+            } else if name_value.path.is_ident("timeout_ms") {
+                action_impl.timeout_ms = Some(expect_positive_int::<u64>(&name_value.value)?);
+            } else if name_value.path.is_ident("on_timeout") {
+                let method = expect_lit_str(&name_value.value)?;
+                action_impl.on_timeout = Some(method.parse::<Ident>().map_err(|_| {
+                    syn::Error::new_spanned(&method, "Expected the name of a method on the service")
+                })?);
+            } else if name_value.path.is_ident("idempotency_cache_size") {
+                action_impl.idempotency_cache_size =
+                    Some(expect_positive_int::<usize>(&name_value.value)?);
+            } else {
+                return Err(syn::Error::new_spanned(
+                    &name_value.path,
+                    "Unknown action option, expected one of: name, path, after, meta, enabled_if, idempotency_key, idempotency_cache_size, schema, raw_payload, max_concurrency, log_args, params_struct, redact, outputs, timeout_ms, on_timeout, respect_deadline, paginate, positional, serializer, progress_topic",
+                ));
+            }
+        }
+
+        // A raw payload is never deserialized, so there is nothing to validate
+        if let (true, Some(schema)) = (action_impl.raw_payload, &action_impl.schema) {
+            return Err(syn::Error::new_spanned(
+                schema,
+                "schema can't be combined with raw_payload",
+            ));
+        }
+
+        // A list payload has no keys, neither for the window, the idempotency key nor a raw value
+        if let Some(positional) = action_impl
+            .positional
+            .as_ref()
+            .filter(|positional| positional.value)
+        {
+            let conflict = if action_impl.paginates() {
+                Some("paginate")
+            } else if action_impl.idempotency_key.is_some() {
+                Some("idempotency_key")
+            } else if action_impl.raw_payload {
+                Some("raw_payload")
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return Err(syn::Error::new_spanned(
+                    positional,
+                    format!("positional can't be combined with {}", conflict),
+                ));
+            }
+        }
+
+        // The serializer converts the parameters and the result as a whole, the options building
+        // their own values don't go through it
+        if let Some(serializer) = &action_impl.serializer {
+            let conflict = if action_impl.raw_payload {
+                Some("raw_payload")
+            } else if action_impl.paginates() {
+                Some("paginate")
+            } else if !action_impl.outputs.is_empty() {
+                Some("outputs")
+            } else if action_impl
+                .params_struct
+                .as_ref()
+                .is_some_and(|params_struct| params_struct.value)
+            {
+                Some("params_struct")
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return Err(syn::Error::new_spanned(
+                    serializer,
+                    format!("serializer can't be combined with {}", conflict),
+                ));
+            }
+        }
+
+        // The cache size only makes sense with a key to cache by
+        if let (None, Some(cache_size)) = (
+            &action_impl.idempotency_key,
+            &action_impl.idempotency_cache_size,
+        ) {
+            return Err(syn::Error::new_spanned(
+                cache_size,
+                "idempotency_cache_size requires idempotency_key",
+            ));
+        }
+
+        // The cleanup hook only runs when the timeout or the caller's deadline fires
+        if let (None, false, Some(on_timeout)) = (
+            &action_impl.timeout_ms,
+            action_impl.respects_deadline(),
+            &action_impl.on_timeout,
+        ) {
+            return Err(syn::Error::new_spanned(
+                on_timeout,
+                "on_timeout requires timeout_ms or respect_deadline = true",
+            ));
+        }
+
+        Ok(action_impl)
+    }
+}
+
+/// Parse the arguments of an `#[action(...)]` attribute found on a method
+pub fn parse_action_attribute(attr: &Attribute) -> syn::Result<ActionImpl> {
+    match &attr.meta {
+        Meta::Path(_) => Ok(ActionImpl::default()),
+        _ => attr.parse_args::<ActionImpl>(),
+    }
+}
+
 /// Implementation of the action macro
 pub fn action_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Parse the input as a function
-    let input = parse_macro_input!(item as ItemFn);
+    let mut input = parse_macro_input!(item as ItemFn);
 
-    // Default to function name
+    // Parse the attributes
+    let action_impl = parse_macro_input!(attr as ActionImpl);
+
+    // Default to function name, a custom name is also used as the path
     let fn_name = input.sig.ident.to_string();
+    let action_name = action_impl.name.clone().unwrap_or(fn_name);
+    let action_path = action_impl
+        .path
+        .clone()
+        .unwrap_or_else(|| action_name.clone());
 
-    // Parse the attributes
-    let mut action_name = fn_name.clone();
-    let mut action_path = fn_name.clone();
-
-    if !attr.is_empty() {
-        // Convert attribute tokens to a string for simple parsing
-        let attr_str = attr.to_string();
-
-        // Extract attributes from the TokenStream
-        if attr_str.contains("path") {
-            // Try to parse as a name-value attribute
-            // For safety, we're using a simple string parsing approach
-            let attr_str = attr.to_string();
-
-            if attr_str.contains("path") && attr_str.contains('=') && attr_str.contains('"') {
-                // Find the path value
-                let start_idx = attr_str.find("path").unwrap() + 4; // Skip 'path'
-                let equals_idx = attr_str[start_idx..].find('=').unwrap() + start_idx + 1; // Skip '='
-                let quote_start_idx = attr_str[equals_idx..].find('"').unwrap() + equals_idx + 1; // Skip opening quote
-                let quote_end_idx =
-                    attr_str[quote_start_idx..].find('"').unwrap() + quote_start_idx;
-
-                // Extract the path value
-                action_path = attr_str[quote_start_idx..quote_end_idx].to_string();
+    // Extract parameters from the function signature, #[request_id], #[cancel] and #[file]
+    // ones aren't payload
+    let call_params = extract_parameters(&input);
+    let request_id_params = extract_marked_parameters(&input, "request_id");
+    let cancel_params = extract_marked_parameters(&input, "cancel");
+    let file_params = extract_marked_parameters(&input, "file");
+    let params = call_params
+        .iter()
+        .filter(|(ident, _)| {
+            !request_id_params
+                .iter()
+                .chain(&cancel_params)
+                .chain(&file_params)
+                .any(|(id, _)| id == ident)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    // Former parameter names, tried after the current one
+    let param_aliases = match extract_parameter_aliases(&input) {
+        Ok(param_aliases) => param_aliases,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Parameters read from the path captures first, then from the payload
+    let path_sourced = extract_marked_parameters(&input, "from_path_or_payload");
+    strip_markers(&mut input.sig, PARAMETER_MARKERS);
+
+    // The request id is handed over as an owned String
+    if let Some((_, param_type)) = request_id_params
+        .iter()
+        .find(|(_, param_type)| quote! { #param_type }.to_string() != "String")
+    {
+        return syn::Error::new_spanned(param_type, "#[request_id] parameters must be a `String`")
+            .to_compile_error()
+            .into();
+    }
+
+    // Idempotency keys are read from the payload as strings
+    if let Some((_, param_type)) = action_impl.idempotency_key.as_ref().and_then(|key_field| {
+        params.iter().find(|(ident, param_type)| {
+            *ident == key_field.value() && quote! { #param_type }.to_string() != "String"
+        })
+    }) {
+        return syn::Error::new_spanned(
+            param_type,
+            "the idempotency_key parameter must be a `String`, the key is read from the payload as a string",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Path captures are strings, parsed into the parameter type
+    if let Some((_, param_type)) = path_sourced.iter().find(|(_, param_type)| {
+        !matches!(
+            quote! { #param_type }.to_string().as_str(),
+            "String" | "i32" | "i64" | "u32" | "u64" | "f32" | "f64" | "bool"
+        )
+    }) {
+        return syn::Error::new_spanned(
+            param_type,
+            "#[from_path_or_payload] parameters must be a `String`, a number or a `bool`, parsed from the path capture",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let path_sourced = path_sourced
+        .into_iter()
+        .map(|(ident, _)| ident)
+        .collect::<Vec<_>>();
+
+    // The cancellation token is handed over as an owned CancellationToken
+    if let Some((_, param_type)) = cancel_params.iter().find(|(_, param_type)| {
+        !matches!(param_type, Type::Path(type_path)
+            if type_path.path.segments.last().is_some_and(|seg| seg.ident == "CancellationToken"))
+    }) {
+        return syn::Error::new_spanned(
+            param_type,
+            "#[cancel] parameters must be a `CancellationToken`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Binary parts arrive as raw bytes, converted into the parameter's byte container
+    if let Some((_, param_type)) = file_params
+        .iter()
+        .find(|(_, param_type)| !is_bytes_type(&quote! { #param_type }.to_string()))
+    {
+        return syn::Error::new_spanned(
+            param_type,
+            "#[file] parameters must be `Vec<u8>` or `Bytes`, read from a binary part of the request",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Read #[request_id], #[cancel] and #[file] parameters from the request context
+    let context_extractions = request_id_params
+        .iter()
+        .map(|(ident, _)| {
+            quote! {
+                let #ident = ctx.request_id().to_string();
             }
-        } else {
-            // Try to parse as a simple string literal for backward compatibility
-            let parser = Punctuated::<Lit, Comma>::parse_terminated;
-            if let Ok(lit_args) = parser.parse(attr.clone()) {
-                if !lit_args.is_empty() {
-                    // Get the first argument as a string literal for the name
-                    if let Lit::Str(s) = &lit_args[0] {
-                        action_name = s.value();
-                        action_path = action_name.clone(); // Use the same value for path if not specified separately
+        })
+        .chain(cancel_params.iter().map(|(ident, _)| {
+            quote! {
+                let #ident = ctx.cancellation_token().clone();
+            }
+        }))
+        .chain(file_params.iter().map(|(ident, param_type)| {
+            // The binary part is named after the parameter
+            let part_name = ident.to_string();
+            quote! {
+                let #ident: #param_type = match ctx.attachment(#part_name) {
+                    Some(part) => part.into(),
+                    None => {
+                        ctx.error(format!("Missing binary part {} for action '{}'", #part_name, #action_name));
+                        return Err(anyhow::anyhow!(format!("Missing binary part {} for action '{}'", #part_name, #action_name)));
                     }
-                }
+                };
             }
-        }
-    };
+        }))
+        .collect::<TokenStream2>();
 
-    // Extract parameters from the function signature
-    let params = extract_parameters(&input);
+    // A raw payload is bound to exactly one ArcValueType parameter
+    if action_impl.raw_payload {
+        let takes_value_type = match params.as_slice() {
+            [(_, param_type)] => {
+                !matches!(param_type, Type::Reference(_))
+                    && quote! { #param_type }.to_string().ends_with("ArcValueType")
+            }
+            _ => false,
+        };
+        if !takes_value_type {
+            return syn::Error::new_spanned(
+                &input.sig.inputs,
+                "#[action(raw_payload)] requires exactly one parameter of type ArcValueType besides the context",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
 
     // Extract the return type information for proper handling
     let return_type_info = extract_return_type_info(&input.sig.output);
 
+    // The handler outlives the call, so the result can't borrow from the service or the request
+    if let Some(borrowed_type) = &return_type_info.borrowed_type {
+        return syn::Error::new_spanned(
+            borrowed_type,
+            "#[action] must return owned, 'static data: the result is sent after the call \
+             returns, so it can't borrow from the service or the request. Return an owned \
+             type instead (e.g. String instead of &str)",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Named outputs map the values of a tuple result one to one
+    if let Some(first_output) = action_impl.outputs.first() {
+        let arity =
+            match extract_result_ok_type(&input.sig.output) {
+                Some(Type::Tuple(tuple)) => tuple.elems.len(),
+                _ => return syn::Error::new_spanned(
+                    &input.sig.output,
+                    "outputs(...) requires the action to return a tuple, e.g. Result<(f64, f64)>",
+                )
+                .to_compile_error()
+                .into(),
+            };
+        if arity != action_impl.outputs.len() {
+            return syn::Error::new_spanned(
+                first_output,
+                format!(
+                    "outputs(...) names {} values but the action returns a tuple of {}",
+                    action_impl.outputs.len(),
+                    arity
+                ),
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    // Only a struct or map result has fields to redact, decided from the result type itself
+    let redactable = extract_result_ok_type(&input.sig.output)
+        .or_else(|| match &input.sig.output {
+            ReturnType::Type(_, ty) => Some((**ty).clone()),
+            ReturnType::Default => None,
+        })
+        .is_some_and(|ty| {
+            let type_str = quote! { #ty }.to_string();
+            map_key_value_types(&ty).is_some()
+                || !(is_primitive_param_type(&type_str)
+                    || is_bytes_type(&type_str)
+                    || type_str == "()")
+        });
+    if let (Some(field), true) = (
+        action_impl.redact.first(),
+        action_impl.outputs.is_empty() && !redactable,
+    ) {
+        return syn::Error::new_spanned(
+            field,
+            "redact only applies to actions returning a struct or a map",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Pagination windows a list result and replaces it with an envelope
+    if let Some(paginate) = action_impl
+        .paginate
+        .as_ref()
+        .filter(|paginate| paginate.value)
+    {
+        let returns_vec = extract_result_ok_type(&input.sig.output)
+            .is_some_and(|ok_type| vec_element_type(&ok_type).is_some());
+        if !returns_vec {
+            return syn::Error::new_spanned(
+                &input.sig.output,
+                "paginate requires the action to return a list, e.g. Result<Vec<T>>",
+            )
+            .to_compile_error()
+            .into();
+        }
+        if let Some(field) = action_impl.redact.first() {
+            return syn::Error::new_spanned(field, "redact can't be combined with paginate")
+                .to_compile_error()
+                .into();
+        }
+        if action_impl.raw_payload {
+            return syn::Error::new_spanned(
+                paginate,
+                "paginate can't be combined with raw_payload",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    // Trait objects such as Box<dyn Any> cannot be converted into an ArcValueType
+    if let Some(boxed_type) = find_boxed_trait_object(&input.sig.output) {
+        return syn::Error::new_spanned(
+            boxed_type,
+            "#[action] cannot return a boxed trait object (e.g. Box<dyn Any>): the result must be \
+             serializable. Return a concrete type implementing Serialize, or build the response \
+             yourself and return runar_common::types::ArcValueType",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Bind a progress sink for the action body, publishing to the resolved progress topic
+    if let Some(progress_topic) = &action_impl.progress_topic {
+        match generate_progress_sink(progress_topic, &call_params, find_context_parameter(&input)) {
+            Ok(progress_sink) => {
+                let block = &input.block;
+                input.block = syn::parse_quote! {{
+                    #progress_sink
+                    #block
+                }};
+            }
+            Err(err) => return err.to_compile_error().into(),
+        }
+    }
+
     // Generate the register action method based on return type information
+    let parameters = ActionParameters {
+        payload: &params,
+        aliases: &param_aliases,
+        path_sourced: &path_sourced,
+        call: &call_params,
+        context_extractions: &context_extractions,
+    };
     let register_action_method = generate_register_action_method(
         &input.sig.ident,
         &action_name,
         &action_path,
-        &params,
+        &parameters,
         &input.sig.output,
-        &return_type_info.is_primitive,
-        &return_type_info.type_name,
-        &return_type_info.needs_registration,
+        &return_type_info,
+        &action_impl,
     );
 
     // Combine the original function with the generated register method
@@ -90,16 +681,92 @@ pub fn action_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     expanded.into()
 }
 
+/// Generate the `ProgressSink` type and the `progress` binding prepended to the action body
+///
+/// Each `{name}` segment of the topic is replaced with the value of the parameter of the
+/// same name when the action is called. The sink borrows the action's context parameter,
+/// and its `emit` bound names `serde::Serialize`, so the user crate must depend on `serde`.
+fn generate_progress_sink(
+    progress_topic: &LitStr,
+    call_params: &[(Ident, Type)],
+    context_ident: Option<Ident>,
+) -> syn::Result<TokenStream2> {
+    let Some(context_ident) = context_ident else {
+        return Err(syn::Error::new_spanned(
+            progress_topic,
+            "progress_topic requires the action to take its RequestContext parameter",
+        ));
+    };
+    let topic = progress_topic.value();
+    let mut format_string = String::new();
+    let mut format_args = Vec::new();
+    for segment in topic.split('/') {
+        if !format_string.is_empty() {
+            format_string.push('/');
+        }
+        match segment
+            .strip_prefix('{')
+            .and_then(|segment| segment.strip_suffix('}'))
+        {
+            Some(name) => {
+                let Some((param_ident, _)) = call_params.iter().find(|(ident, _)| ident == name)
+                else {
+                    return Err(syn::Error::new_spanned(
+                        progress_topic,
+                        format!(
+                            "progress_topic segment {{{}}} doesn't match a parameter of the action",
+                            name
+                        ),
+                    ));
+                };
+                format_string.push_str("{}");
+                format_args.push(param_ident.clone());
+            }
+            None => format_string.push_str(&segment.replace('{', "{{").replace('}', "}}")),
+        }
+    }
+
+    Ok(quote! {
+        /// Publishes intermediate results of the action to its progress topic
+        struct ProgressSink<'a> {
+            ctx: &'a runar_node::services::RequestContext,
+            topic: String,
+        }
+
+        impl ProgressSink<'_> {
+            /// Publish an intermediate result, the action still returns its final result
+            #[allow(dead_code)]
+            async fn emit<T: serde::Serialize + Send + Sync + 'static>(&self, update: T) -> anyhow::Result<()> {
+                self.ctx
+                    .publish(&self.topic, Some(runar_common::types::ArcValueType::from_struct(update)))
+                    .await
+            }
+
+            /// The topic progress is published to, with the parameters filled in
+            #[allow(dead_code)]
+            fn topic(&self) -> &str {
+                &self.topic
+            }
+        }
+
+        let progress = ProgressSink {
+            ctx: #context_ident,
+            topic: format!(#format_string, #(#format_args),*),
+        };
+    })
+}
+
 /// Extract information about the return type for proper handling.
 /// This function robustly supports all valid Rust types, including nested generics.
 fn extract_return_type_info(return_type: &ReturnType) -> ReturnTypeInfo {
-    use syn::{Type, PathArguments, GenericArgument};
+    use syn::{GenericArgument, PathArguments, Type};
     match return_type {
         ReturnType::Default => ReturnTypeInfo {
             is_result: false,
-            type_name: "()".to_string(),
             is_primitive: true,
-            needs_registration: false,
+            is_value_type: false,
+            is_bytes: false,
+            borrowed_type: None,
         },
         ReturnType::Type(_, ty) => {
             // Helper: recursively extract the first type parameter of Result<T, E>
@@ -126,42 +793,122 @@ fn extract_return_type_info(return_type: &ReturnType) -> ReturnTypeInfo {
                 (false, &**ty)
             };
 
-
             let type_name = quote! { #inner_type_ast }.to_string();
 
+            // Maps keyed by anything but String are serialized as structs, whatever their
+            // key and value types contain
+            let has_non_string_keys = map_key_value_types(inner_type_ast)
+                .is_some_and(|(key_type, _)| quote! { #key_type }.to_string() != "String");
+
             // Determine if this is a primitive type
-            let is_primitive = type_name.contains("i32")
-                || type_name.contains("i64")
-                || type_name.contains("u32")
-                || type_name.contains("u64")
-                || type_name.contains("f32")
-                || type_name.contains("f64")
-                || type_name.contains("bool")
-                || type_name.contains("String")
-                || type_name.contains("&str")
-                || type_name.contains("()");
-
-            // Determine if this type needs registration with the serializer
-            let needs_registration =
-                !is_primitive && !type_name.contains("Vec") && !type_name.contains("HashMap");
+            let is_primitive = !has_non_string_keys
+                && (type_name.contains("i32")
+                    || type_name.contains("i64")
+                    || type_name.contains("u32")
+                    || type_name.contains("u64")
+                    || type_name.contains("f32")
+                    || type_name.contains("f64")
+                    || type_name.contains("bool")
+                    || type_name.contains("String")
+                    || type_name.contains("&str")
+                    || type_name.contains("()"));
+
+            // An ArcValueType result is already a response value and is passed through as-is
+            let is_value_type = matches!(inner_type_ast, Type::Path(type_path)
+                if type_path.path.segments.last().is_some_and(|seg| seg.ident == "ArcValueType"));
+
+            // Binary results (Vec<u8> or bytes::Bytes) are sent as raw bytes
+            let is_bytes = is_bytes_type(&type_name);
 
             ReturnTypeInfo {
                 is_result,
-                type_name,
                 is_primitive,
-                needs_registration,
+                is_value_type,
+                is_bytes,
+                borrowed_type: find_borrowed_type(ty).cloned(),
             }
         }
     }
 }
 
+/// Find a boxed trait object (e.g. `Box<dyn Any>`) used as the action's result type
+fn find_boxed_trait_object(return_type: &ReturnType) -> Option<&Type> {
+    use syn::{GenericArgument, PathArguments};
+
+    fn boxed_trait_object(ty: &Type) -> Option<&Type> {
+        let Type::Path(type_path) = ty else {
+            return None;
+        };
+        let seg = type_path.path.segments.last()?;
+        let PathArguments::AngleBracketed(ref ab) = seg.arguments else {
+            return None;
+        };
+        let first_type = ab.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(inner_ty) => Some(inner_ty),
+            _ => None,
+        })?;
+        if seg.ident == "Box" && matches!(first_type, Type::TraitObject(_)) {
+            Some(ty)
+        } else if seg.ident == "Result" {
+            boxed_trait_object(first_type)
+        } else {
+            None
+        }
+    }
+
+    match return_type {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ty) => boxed_trait_object(ty),
+    }
+}
+
+/// Find the part of a type that borrows with a non-`'static` lifetime, such as `&str` or `Cow<'a, str>`
+fn find_borrowed_type(ty: &Type) -> Option<&Type> {
+    use syn::{GenericArgument, PathArguments};
+
+    let is_static = |lifetime: &syn::Lifetime| lifetime.ident == "static";
+    match ty {
+        Type::Reference(type_ref) => match &type_ref.lifetime {
+            Some(lifetime) if is_static(lifetime) => find_borrowed_type(&type_ref.elem),
+            _ => Some(ty),
+        },
+        Type::Path(type_path) => {
+            for seg in &type_path.path.segments {
+                let PathArguments::AngleBracketed(ref ab) = seg.arguments else {
+                    continue;
+                };
+                for arg in &ab.args {
+                    match arg {
+                        GenericArgument::Lifetime(lifetime) if !is_static(lifetime) => {
+                            return Some(ty)
+                        }
+                        GenericArgument::Type(inner_ty) => {
+                            if let Some(borrowed) = find_borrowed_type(inner_ty) {
+                                return Some(borrowed);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            None
+        }
+        Type::Tuple(tuple) => tuple.elems.iter().find_map(find_borrowed_type),
+        Type::Array(array) => find_borrowed_type(&array.elem),
+        Type::Slice(slice) => find_borrowed_type(&slice.elem),
+        Type::Paren(paren) => find_borrowed_type(&paren.elem),
+        Type::Group(group) => find_borrowed_type(&group.elem),
+        _ => None,
+    }
+}
 
 /// Struct to hold information about the return type
 struct ReturnTypeInfo {
-    is_result: bool,          // Whether the return type is a Result
-    type_name: String,        // The name of the type (or inner type if Result)
-    is_primitive: bool,       // Whether it's a primitive type
-    needs_registration: bool, // Whether it needs registration with the serializer
+    is_result: bool,             // Whether the return type is a Result
+    is_primitive: bool,          // Whether it's a primitive type
+    is_value_type: bool,         // Whether it's already an ArcValueType
+    is_bytes: bool,              // Whether it's a binary payload (Vec<u8> or Bytes)
+    borrowed_type: Option<Type>, // A part of the type borrowing with a non-'static lifetime
 }
 
 /// Extract parameters from the function signature
@@ -170,13 +917,14 @@ fn extract_parameters(input: &ItemFn) -> Vec<(Ident, Type)> {
 
     for arg in &input.sig.inputs {
         match arg {
-            FnArg::Typed(PatType { pat, ty, .. }) => {
-                // Skip the context parameter
+            FnArg::Typed(PatType { pat, ty, attrs, .. }) => {
+                // Skip the context parameter, unless marked with #[param]
                 if let Pat::Ident(PatIdent { ident, .. }) = &**pat {
                     let ident_string = ident.to_string();
-                    if ident_string != "self"
-                        && ident_string != "ctx"
-                        && !ident_string.ends_with("ctx")
+                    if has_marker(attrs, "param")
+                        || (ident_string != "self"
+                            && ident_string != "ctx"
+                            && !ident_string.ends_with("ctx"))
                     {
                         params.push((ident.clone(), (**ty).clone()));
                     }
@@ -189,32 +937,295 @@ fn extract_parameters(input: &ItemFn) -> Vec<(Ident, Type)> {
     params
 }
 
+/// Find the context parameter, the one `extract_parameters` skips
+fn find_context_parameter(input: &ItemFn) -> Option<Ident> {
+    input.sig.inputs.iter().find_map(|arg| match arg {
+        FnArg::Typed(PatType { pat, attrs, .. }) => match &**pat {
+            Pat::Ident(PatIdent { ident, .. })
+                if !has_marker(attrs, "param") && ident.to_string().ends_with("ctx") =>
+            {
+                Some(ident.clone())
+            }
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Extract the `#[alias(...)]` former names of the parameters
+fn extract_parameter_aliases(input: &ItemFn) -> syn::Result<HashMap<Ident, Vec<LitStr>>> {
+    let mut param_aliases = HashMap::new();
+
+    for arg in &input.sig.inputs {
+        if let FnArg::Typed(PatType { pat, attrs, .. }) = arg {
+            let aliases = parameter_aliases(attrs)?;
+            if let (false, Pat::Ident(PatIdent { ident, .. })) = (aliases.is_empty(), &**pat) {
+                param_aliases.insert(ident.clone(), aliases);
+            }
+        }
+    }
+
+    Ok(param_aliases)
+}
+
+/// Extract parameters carrying a marker such as #[request_id] or #[cancel], which receive
+/// their value from the context
+fn extract_marked_parameters(input: &ItemFn, marker: &str) -> Vec<(Ident, Type)> {
+    let mut params = Vec::new();
+
+    for arg in &input.sig.inputs {
+        if let FnArg::Typed(PatType { pat, ty, attrs, .. }) = arg {
+            if let (true, Pat::Ident(PatIdent { ident, .. })) = (has_marker(attrs, marker), &**pat)
+            {
+                params.push((ident.clone(), (**ty).clone()));
+            }
+        }
+    }
+
+    params
+}
+
+/// Parameters of an action, grouped by where the handler reads them from
+struct ActionParameters<'a> {
+    /// Read from the payload, by name or by one of their aliases
+    payload: &'a [(Ident, Type)],
+    aliases: &'a HashMap<Ident, Vec<LitStr>>,
+    /// Read from the path captures before the payload
+    path_sourced: &'a [Ident],
+    /// All the arguments passed to the method before the context, in declaration order
+    call: &'a [(Ident, Type)],
+    /// Bindings of the arguments read from the request context
+    context_extractions: &'a TokenStream2,
+}
+
 /// Generate the register action method
 fn generate_register_action_method(
     fn_ident: &Ident,
     action_name: &str,
     action_path: &str,
-    params: &[(Ident, Type)],
+    parameters: &ActionParameters,
     return_type: &ReturnType,
-    is_primitive: &bool,
-    type_name: &String,
-    needs_registration: &bool,
+    return_type_info: &ReturnTypeInfo,
+    action_impl: &ActionImpl,
 ) -> TokenStream2 {
-    // Create a boolean expression for checking if there are parameters
-    let has_params = if params.is_empty() {
-        quote! { false }
+    let ActionParameters {
+        payload: params,
+        aliases: param_aliases,
+        path_sourced,
+        call: call_params,
+        context_extractions,
+    } = parameters;
+    let ReturnTypeInfo {
+        is_primitive,
+        is_value_type,
+        is_bytes,
+        ..
+    } = return_type_info;
+    // Bind the payload, falling back to a null value or an error when the request carries none
+    let payload_binding = if action_impl.raw_payload {
+        quote! {
+            // The raw payload parameter receives a null value when there is no payload
+            let params_value = params_opt.unwrap_or_else(runar_common::types::ArcValueType::null);
+        }
+    } else if params.is_empty() && action_impl.idempotency_key.is_some() {
+        quote! {
+            // Only the idempotency key is read from the payload
+            let params_value = params_opt.unwrap_or_else(runar_common::types::ArcValueType::null);
+        }
+    } else if params.is_empty() {
+        quote! {
+            // The action takes no parameters, so the payload is never read
+            let _ = params_opt;
+        }
+    } else if params.iter().all(|(ident, _)| path_sourced.contains(ident)) {
+        quote! {
+            // Every parameter can come from the path, so the payload is optional
+            let mut params_value = params_opt.unwrap_or_else(runar_common::types::ArcValueType::null);
+        }
+    } else {
+        quote! {
+            // Extract parameters from the map if available
+            let mut params_value = match params_opt {
+                Some(p) => p,
+                None => {
+                    ctx.error("No parameters provided".to_string());
+                    return Err(anyhow::anyhow!("No parameters provided"));
+                }
+            };
+        }
+    };
+
+    // Read the page window before the payload is handed to the parameters
+    let payload_binding = if action_impl.paginates() {
+        let (err_pat, err_detail) = action_impl.error_tokens();
+        quote! {
+            let page_map = params_opt
+                .clone()
+                .and_then(|mut value| value.as_map_ref::<String, runar_common::types::ArcValueType>().ok())
+                .unwrap_or_default();
+            let page_cursor = match page_map.get("cursor") {
+                Some(value) => match value.clone().as_type::<usize>() {
+                    Ok(cursor) => cursor,
+                    Err(#err_pat) => {
+                        ctx.error(format!("Invalid pagination cursor: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Invalid pagination cursor: {}", #err_detail)));
+                    }
+                },
+                None => 0,
+            };
+            let page_limit = match page_map.get("limit") {
+                Some(value) => match value.clone().as_type::<usize>() {
+                    Ok(0) => {
+                        ctx.error("Invalid pagination limit: must be positive".to_string());
+                        return Err(anyhow::anyhow!("Invalid pagination limit: must be positive"));
+                    }
+                    Ok(limit) => Some(limit),
+                    Err(#err_pat) => {
+                        ctx.error(format!("Invalid pagination limit: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Invalid pagination limit: {}", #err_detail)));
+                    }
+                },
+                None => None,
+            };
+            #payload_binding
+        }
+    } else {
+        payload_binding
+    };
+
+    // Generate parameter extraction code, a raw payload is passed through untouched
+    let param_extractions = if action_impl.raw_payload {
+        let (param_ident, _) = &params[0];
+        quote! {
+            let #param_ident = params_value;
+        }
     } else {
-        quote! { true }
+        generate_parameter_extractions(params, param_aliases, path_sourced, action_impl)
     };
 
-    // Generate parameter extraction code
-    let param_extractions = generate_parameter_extractions(params);
+    // Parameters read from the request context come first
+    let param_extractions = quote! {
+        #context_extractions
+        #param_extractions
+    };
 
     // Generate method call with extracted parameters
-    let method_call = generate_method_call(fn_ident, params);
+    let method_call = generate_method_call(fn_ident, call_params);
 
     // Generate the appropriate result handling based on the return type
-    let result_handling = if *is_primitive {
+    let redaction = if action_impl.redact.is_empty() {
+        TokenStream2::new()
+    } else {
+        let redacted_fields = &action_impl.redact;
+        quote! {
+            // Remove the redacted fields, results that aren't a map are returned unchanged
+            let mut value_type = value_type;
+            let value_type = match value_type.as_map_ref::<String, runar_common::types::ArcValueType>() {
+                Ok(map) => {
+                    let mut map = (*map).clone();
+                    #(map.remove(#redacted_fields);)*
+                    runar_common::types::ArcValueType::new_map(map)
+                }
+                Err(_) => value_type,
+            };
+        }
+    };
+    // A map result has its redacted keys removed before it's converted, whatever its value type
+    let map_redaction = match extract_result_ok_type(return_type) {
+        Some(ok_type)
+            if !action_impl.redact.is_empty() && map_key_value_types(&ok_type).is_some() =>
+        {
+            let redacted_fields = &action_impl.redact;
+            quote! {
+                let mut result = result;
+                #(result.remove(#redacted_fields);)*
+            }
+        }
+        _ => TokenStream2::new(),
+    };
+    let result_handling = if !action_impl.outputs.is_empty() {
+        // The tuple arity was checked against the output names by the action macro
+        let output_types = match extract_result_ok_type(return_type) {
+            Some(Type::Tuple(tuple)) => tuple.elems.into_iter().collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+        let output_names = action_impl.outputs.iter().map(|output| output.to_string());
+        let output_idents = action_impl
+            .outputs
+            .iter()
+            .map(|output| format_ident!("output_{}", output))
+            .collect::<Vec<_>>();
+        let output_values = output_idents.iter().zip(&output_types).map(|(ident, ty)| {
+            let type_str = quote! { #ty }.to_string();
+            if type_str.ends_with("ArcValueType") {
+                quote! { #ident }
+            } else if is_primitive_param_type(&type_str) {
+                quote! { runar_common::types::ArcValueType::new_primitive(#ident) }
+            } else {
+                quote! { runar_common::types::ArcValueType::from_struct(#ident) }
+            }
+        });
+        quote! {
+            // Return the tuple's values as a map keyed by the output names
+            let (#(#output_idents,)*) = result;
+            let mut outputs = std::collections::HashMap::<String, runar_common::types::ArcValueType>::new();
+            #(outputs.insert(#output_names.to_string(), #output_values);)*
+            let value_type = runar_common::types::ArcValueType::new_map(outputs);
+            #redaction
+            Ok(Some(value_type))
+        }
+    } else if action_impl.paginates() {
+        quote! {
+            // Return the requested page of the list in a { items, next_cursor, total } envelope
+            let total = result.len();
+            let page_start = page_cursor.min(total);
+            let page_end = page_limit.map_or(total, |limit| page_start.saturating_add(limit).min(total));
+            let items = result
+                .into_iter()
+                .skip(page_start)
+                .take(page_end - page_start)
+                .collect::<Vec<_>>();
+            let mut envelope = std::collections::HashMap::<String, runar_common::types::ArcValueType>::new();
+            envelope.insert("items".to_string(), runar_common::types::ArcValueType::from_struct(items));
+            envelope.insert(
+                "next_cursor".to_string(),
+                if page_end < total {
+                    runar_common::types::ArcValueType::new_primitive(page_end as u64)
+                } else {
+                    runar_common::types::ArcValueType::null()
+                },
+            );
+            envelope.insert("total".to_string(), runar_common::types::ArcValueType::new_primitive(total as u64));
+            let value_type = runar_common::types::ArcValueType::new_map(envelope);
+            Ok(Some(value_type))
+        }
+    } else if let (Some(serializer), false) = (&action_impl.serializer, *is_value_type) {
+        quote! {
+            // Encode the result with the action's serializer instead of the default conversion
+            let value_type = match <#serializer>::encode(&result) {
+                Ok(value_type) => value_type,
+                Err(err) => {
+                    ctx.error(format!("Failed to encode the result of action '{}': {}", #action_name, err));
+                    return Err(anyhow::anyhow!(format!("Failed to encode the result of action '{}': {}", #action_name, err)));
+                }
+            };
+            #redaction
+            Ok(Some(value_type))
+        }
+    } else if *is_value_type {
+        quote! {
+            // The action built its own ArcValueType, so return it unchanged
+            let value_type = result;
+            #redaction
+            Ok(Some(value_type))
+        }
+    } else if *is_bytes {
+        quote! {
+            // Send binary results as raw bytes instead of a serialized struct
+            let value_type = runar_common::types::ArcValueType::new_bytes(Vec::<u8>::from(result));
+            Ok(Some(value_type))
+        }
+    } else if *is_primitive {
         quote! {
             // Convert the result to ArcValueType
             let value_type = runar_common::types::ArcValueType::new_primitive(result);
@@ -224,65 +1235,387 @@ fn generate_register_action_method(
         quote! {
             // Convert the complex result to ArcValueType using appropriate value category
             let value_type = runar_common::types::ArcValueType::from_struct(result);
+            #redaction
             Ok(Some(value_type))
         }
     };
 
-    // Generate a unique method name for the action registration
+    let result_handling = quote! {
+        #map_redaction
+        #result_handling
+    };
+
+    // Run the post-processing hook on the successful result, if configured
+    let post_processing = match &action_impl.after {
+        Some(after) => quote! {
+            let result = match inner_self.#after(result, &ctx).await {
+                Ok(result) => result,
+                Err(err) => {
+                    ctx.error(format!("Post-processing for action '{}' failed: {}", #action_name, err));
+                    return Err(anyhow::anyhow!(err.to_string()));
+                }
+            };
+        },
+        None => TokenStream2::new(),
+    };
+
+    // Remember responses by idempotency key so retried requests aren't executed twice
+    // The idempotency key is part of the payload, so it is only logged when arguments may be
+    let cached_response_log = if action_impl.redacts_args() {
+        quote! {
+            ctx.debug(format!("Returning cached response for action '{}'", #action_name));
+        }
+    } else {
+        quote! {
+            ctx.debug(format!("Returning cached response for action '{}' with idempotency key {}", #action_name, key));
+        }
+    };
+
+    let (idempotency_setup, idempotency_clone, idempotency_lookup, idempotency_store) =
+        match &action_impl.idempotency_key {
+            Some(key_field) => {
+                let cache_size = match &action_impl.idempotency_cache_size {
+                    Some(cache_size) => quote! { #cache_size },
+                    None => quote! { 1024 },
+                };
+                (
+                    quote! {
+                        // Cached responses by key, plus the keys from least to most recently used
+                        let idempotency_cache: std::sync::Arc<std::sync::Mutex<(
+                            std::collections::HashMap<String, Option<runar_common::types::ArcValueType>>,
+                            std::collections::VecDeque<String>,
+                        )>> = std::sync::Arc::new(std::sync::Mutex::new(Default::default()));
+                    },
+                    quote! {
+                        let idempotency_cache = idempotency_cache.clone();
+                    },
+                    quote! {
+                        // Return the stored response when this key was already handled
+                        let idempotency_key = match params_value.clone().as_map_ref::<String, runar_common::types::ArcValueType>() {
+                            Ok(map) => map.get(#key_field).and_then(|value| value.clone().as_type::<String>().ok()),
+                            Err(_) => None,
+                        };
+                        if let Some(key) = &idempotency_key {
+                            let cached = {
+                                let mut cache = idempotency_cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                                let (responses, keys) = &mut *cache;
+                                let cached = responses.get(key).cloned();
+                                // A hit makes the key the most recently used, the last to be evicted
+                                if cached.is_some() {
+                                    if let Some(position) = keys.iter().position(|used| used == key) {
+                                        if let Some(used) = keys.remove(position) {
+                                            keys.push_back(used);
+                                        }
+                                    }
+                                }
+                                cached
+                            };
+                            if let Some(cached) = cached {
+                                #cached_response_log
+                                return Ok(cached);
+                            }
+                        }
+                    },
+                    quote! {
+                        if let (Some(key), Ok(value)) = (idempotency_key, &response) {
+                            let mut cache = idempotency_cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                            let (responses, keys) = &mut *cache;
+                            if responses.insert(key.clone(), value.clone()).is_none() {
+                                keys.push_back(key);
+                                if keys.len() > #cache_size {
+                                    if let Some(oldest) = keys.pop_front() {
+                                        responses.remove(&oldest);
+                                    }
+                                }
+                            }
+                        }
+                    },
+                )
+            }
+            None => (
+                TokenStream2::new(),
+                TokenStream2::new(),
+                TokenStream2::new(),
+                TokenStream2::new(),
+            ),
+        };
+
+    // Deserialization and validation errors can quote the offending value, so with
+    // log_args = false only the failing location is reported
+    let (err_pat, err_detail) = action_impl.error_tokens();
+    let describe_violation = if action_impl.redacts_args() {
+        quote! { format!("{}: violates {}", error.instance_path, error.schema_path) }
+    } else {
+        quote! { format!("{}: {}", error.instance_path, error) }
+    };
+
+    // Validate the incoming payload against the action's JSON schema
+    let (schema_setup, schema_clone, schema_validation) = match &action_impl.schema {
+        Some(schema_path) => {
+            (
+                quote! {
+                    // The schema is embedded at compile time and compiled once at registration
+                    let schema_validator = {
+                        let schema: serde_json::Value = serde_json::from_str(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", #schema_path)))
+                            .map_err(|err| anyhow::anyhow!(format!("Invalid JSON schema for action '{}': {}", #action_name, err)))?;
+                        std::sync::Arc::new(
+                            jsonschema::validator_for(&schema)
+                                .map_err(|err| anyhow::anyhow!(format!("Invalid JSON schema for action '{}': {}", #action_name, err)))?,
+                        )
+                    };
+                },
+                quote! {
+                    let schema_validator = schema_validator.clone();
+                },
+                quote! {
+                    // The payload is checked as sent, before any parameter is extracted from it
+                    let payload_json = match params_opt.clone() {
+                        Some(mut payload) => payload.as_type::<serde_json::Value>(),
+                        None => Ok(serde_json::Value::Null),
+                    };
+                    let payload_json = match payload_json {
+                        Ok(payload_json) => payload_json,
+                        Err(#err_pat) => {
+                            ctx.error(format!("Failed to read the payload for validation: {}", #err_detail));
+                            return Err(anyhow::anyhow!(format!("Failed to read the payload for validation: {}", #err_detail)));
+                        }
+                    };
+                    // Report every violation with the location it was found at
+                    let validation_errors = schema_validator
+                        .iter_errors(&payload_json)
+                        .map(|error| #describe_violation)
+                        .collect::<Vec<_>>();
+                    if !validation_errors.is_empty() {
+                        ctx.error(format!("Validation failed for action '{}': {:?}", #action_name, validation_errors));
+                        return Err(anyhow::anyhow!(format!("Validation failed for action '{}': {:?}", #action_name, validation_errors)));
+                    }
+                },
+            )
+        }
+        None => (
+            TokenStream2::new(),
+            TokenStream2::new(),
+            TokenStream2::new(),
+        ),
+    };
+
+    // Cancel the method call when it runs past the timeout, calling the cleanup hook first.
+    // The hook is called with the span of its name, so a missing method or a signature other
+    // than `async fn(&self, &RequestContext)` is reported on the attribute.
+    let cleanup = match &action_impl.on_timeout {
+        Some(on_timeout) => quote_spanned! {on_timeout.span()=>
+            let () = inner_self.#on_timeout(&ctx).await;
+        },
+        None => TokenStream2::new(),
+    };
+    let is_timed = action_impl.timeout_ms.is_some() || action_impl.respects_deadline();
+    let (timeout_setup, method_result) = if is_timed {
+        // The action gets the smaller of its own timeout and the time left before the deadline
+        let action_budget = match &action_impl.timeout_ms {
+            Some(timeout_ms) => quote! { Some(std::time::Duration::from_millis(#timeout_ms)) },
+            None => quote! { None },
+        };
+        let deadline_budget = if action_impl.respects_deadline() {
+            quote! {
+                ctx.deadline()
+                    .map(|deadline| deadline.saturating_duration_since(std::time::Instant::now()))
+            }
+        } else {
+            quote! { None }
+        };
+        (
+            quote! {
+                // The budget starts before waiting for a concurrency permit, so the wait counts
+                let action_budget: Option<std::time::Duration> = #action_budget;
+                let deadline_budget: Option<std::time::Duration> = #deadline_budget;
+                let budget = match (action_budget, deadline_budget) {
+                    (Some(action_budget), Some(deadline_budget)) => Some(action_budget.min(deadline_budget)),
+                    (action_budget, deadline_budget) => action_budget.or(deadline_budget),
+                };
+                let timeout_reason = if deadline_budget.is_some() && deadline_budget == budget {
+                    "ran past the request deadline"
+                } else {
+                    "timed out"
+                };
+                let budget_ms = budget.map_or(0, |budget| budget.as_millis());
+                let timeout_at = budget.map(|budget| tokio::time::Instant::now() + budget);
+            },
+            quote! {
+                match timeout_at {
+                    Some(timeout_at) => match tokio::time::timeout_at(timeout_at, #method_call).await {
+                        Ok(result) => result,
+                        Err(_) => {
+                            #cleanup
+                            ctx.error(format!("Action '{}' {} after {} ms", #action_name, timeout_reason, budget_ms));
+                            return Err(anyhow::anyhow!(format!("Action '{}' {} after {} ms", #action_name, timeout_reason, budget_ms)));
+                        }
+                    },
+                    None => #method_call.await,
+                }
+            },
+        )
+    } else {
+        (TokenStream2::new(), quote! { #method_call.await })
+    };
+
+    // Limit the number of concurrent invocations of the action with a semaphore
+    let (concurrency_setup, concurrency_clone, concurrency_acquire) = match &action_impl
+        .max_concurrency
+    {
+        Some(max_concurrency) => {
+            let acquire_failed = quote! {
+                ctx.error(format!("Failed to acquire permit for action '{}': {}", #action_name, err));
+                return Err(anyhow::anyhow!(format!("Failed to acquire permit for action '{}': {}", #action_name, err)));
+            };
+            // With a timeout, waiting for the permit is bounded by the same budget as the call
+            let acquire = if is_timed {
+                quote! {
+                    let concurrency_permit = match timeout_at {
+                        Some(timeout_at) => tokio::time::timeout_at(timeout_at, concurrency_limit.acquire()).await,
+                        None => Ok(concurrency_limit.acquire().await),
+                    };
+                    let _concurrency_permit = match concurrency_permit {
+                        Ok(Ok(permit)) => permit,
+                        Ok(Err(err)) => {
+                            #acquire_failed
+                        }
+                        Err(_) => {
+                            ctx.error(format!("Action '{}' {} after {} ms waiting for a permit", #action_name, timeout_reason, budget_ms));
+                            return Err(anyhow::anyhow!(format!("Action '{}' {} after {} ms waiting for a permit", #action_name, timeout_reason, budget_ms)));
+                        }
+                    };
+                }
+            } else {
+                quote! {
+                    let _concurrency_permit = match concurrency_limit.acquire().await {
+                        Ok(permit) => permit,
+                        Err(err) => {
+                            #acquire_failed
+                        }
+                    };
+                }
+            };
+            (
+                quote! {
+                    // Requests beyond the limit wait for a permit instead of being rejected
+                    let concurrency_limit = std::sync::Arc::new(tokio::sync::Semaphore::new(#max_concurrency));
+                },
+                quote! {
+                    let concurrency_limit = concurrency_limit.clone();
+                },
+                acquire,
+            )
+        }
+        None => (
+            TokenStream2::new(),
+            TokenStream2::new(),
+            TokenStream2::new(),
+        ),
+    };
+
+    // Only successful responses are cached
+    let success_handling = if action_impl.idempotency_key.is_some() {
+        quote! {
+            #post_processing
+            let response = { #result_handling };
+            #idempotency_store
+            response
+        }
+    } else {
+        quote! {
+            #post_processing
+            #result_handling
+        }
+    };
+
+    // Generate unique method names for the action handler and its registration
+    let handler_method_name = format_ident!("action_handler_{}", fn_ident);
     let register_method_name = format_ident!("register_action_{}", fn_ident);
 
+    // The annotations are collected into the service's action_annotations() map
+    let annotations_method_name = format_ident!("action_annotations_{}", fn_ident);
+    // log_args = false is recorded as an annotation so tooling can redact the action's payloads
+    let mut annotations = action_impl.annotations.clone();
+    if action_impl.redacts_args() {
+        annotations.push(("log_args".to_string(), "false".to_string()));
+    }
+    let annotation_keys = annotations.iter().map(|(key, _)| key);
+    let annotation_values = annotations.iter().map(|(_, value)| value);
+
     quote! {
-        async fn #register_method_name(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
-            context.logger.info(format!("Registering '{}' action", #action_name));
+        #[doc(hidden)]
+        fn #annotations_method_name() -> std::collections::HashMap<String, String> {
+            std::collections::HashMap::from([
+                #((#annotation_keys.to_string(), #annotation_values.to_string()),)*
+            ])
+        }
 
-            // Create a clone of self that can be moved into the closure
-            let self_clone = self.clone();
+        #[doc(hidden)]
+        #[allow(
+            unused_mut,
+            clippy::type_complexity,
+            clippy::needless_return,
+            clippy::redundant_clone
+        )]
+        fn #handler_method_name(&self) -> anyhow::Result<runar_node::services::ActionHandler>
+        where
+            // Holds for any service the AbstractService impl accepts, a service that isn't
+            // thread-safe is only reported by the service's Send + Sync assertion
+            for<'__service> Self: Send + Sync,
+        {
+            // Clone the service once and share it, each request only clones the Arc
+            let self_clone = std::sync::Arc::new(self.clone());
+
+            #idempotency_setup
+            #schema_setup
+            #concurrency_setup
 
             // Create the action handler as an Arc to match what the register_action expects
-            let handler = std::sync::Arc::new(move |params_opt: Option<runar_common::types::ArcValueType>, ctx: runar_node::services::RequestContext|
+            let handler: runar_node::services::ActionHandler = std::sync::Arc::new(move |params_opt: Option<runar_common::types::ArcValueType>, ctx: runar_node::services::RequestContext|
                 -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Option<runar_common::types::ArcValueType>, anyhow::Error>> + Send>> {
                 let inner_self = self_clone.clone();
+                #idempotency_clone
+                #schema_clone
+                #concurrency_clone
 
                 Box::pin(async move {
-                    // Extract parameters from the map if available
-                    let mut params_value = match params_opt {
-                        Some(p) => p,
-                        None => {
-                            // Check if method expects parameters
-                            if #has_params {
-                                ctx.error("No parameters provided".to_string());
-                                return Err(anyhow!("No parameters provided"));
-                            } else {
-                                // No parameters expected, so create an empty map
-                                runar_common::types::ArcValueType::new_map(
-                                    std::collections::HashMap::<String, runar_common::types::ArcValueType>::new()
-                                )
-                            }
-                        }
-                    };
+                    #schema_validation
+
+                    #payload_binding
+
+                    #idempotency_lookup
 
                     #param_extractions
 
+                    #timeout_setup
+
+                    #concurrency_acquire
+
                     // Call the actual method with the extracted parameters
-                    match #method_call.await {
+                    match #method_result {
                         Ok(result) => {
-                            #result_handling
+                            #success_handling
                         },
                         Err(err) => {
                             // Return an error response
                             ctx.error(format!("Action '{}' failed: {}", #action_name, err));
-                            return Err(anyhow!(err.to_string()));
+                            return Err(anyhow::anyhow!(err.to_string()));
                         }
                     }
                 })
             });
 
-            // If this action returns a type that needs registration with the serializer,
-            // we would register it here
-            if #needs_registration {
-                context.logger.debug(format!("Type registration needed for action '{}' with type: {}", #action_name, #type_name));
-                // The actual registration logic would depend on the service's serializer API
-            }
+            Ok(handler)
+        }
+
+        #[doc(hidden)]
+        async fn #register_method_name(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()>
+        where
+            for<'__service> Self: Send + Sync,
+        {
+            context.logger.info(format!("Registering '{}' action", #action_name));
+
+            let handler = self.#handler_method_name()?;
 
             // Register the action handler with the configured path
             context.register_action(
@@ -294,164 +1627,459 @@ fn generate_register_action_method(
 }
 
 /// Generate parameter extraction code to exactly match the reference implementation
-fn generate_parameter_extractions(params: &[(Ident, Type)]) -> TokenStream2 {
+fn generate_parameter_extractions(
+    params: &[(Ident, Type)],
+    param_aliases: &HashMap<Ident, Vec<LitStr>>,
+    path_sourced: &[Ident],
+    action_impl: &ActionImpl,
+) -> TokenStream2 {
     let mut extractions = TokenStream2::new();
+    let (err_pat, err_detail) = action_impl.error_tokens();
+
+    // Without parameters the payload is never read
+    if params.is_empty() {
+        return extractions;
+    }
+
+    // A positional payload is a list read by index in declaration order, even for one parameter
+    if action_impl.is_positional() {
+        // Path-sourced parameters may leave the payload empty, so a missing list only
+        // fails for the parameters that are actually looked up in it
+        if !path_sourced.is_empty() {
+            extractions.extend(quote! {
+                let params_list = params_value
+                    .as_list_ref::<runar_common::types::ArcValueType>()
+                    .unwrap_or_default();
+            });
+        } else {
+            extractions.extend(quote! {
+                let params_list = match params_value.as_list_ref::<runar_common::types::ArcValueType>() {
+                    Ok(list) => list,
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameters as list: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameters as list: {}", #err_detail)));
+                    }
+                };
+            });
+        }
+
+        for (index, (param_ident, param_type)) in params.iter().enumerate() {
+            let param_name = param_ident.to_string();
+            let type_str = quote! { #param_type }.to_string();
+
+            // Cow<str> and &str are read as an owned String
+            let (value_type, wrap) = if is_cow_str_type(&type_str) {
+                (quote! { String }, quote! { std::borrow::Cow::Owned(val) })
+            } else if type_str == "& str" {
+                (quote! { String }, quote! { val })
+            } else {
+                (quote! { #param_type }, quote! { val })
+            };
+            let decoded = action_impl.decode_tokens(quote! { value.clone() }, &value_type);
+
+            let extraction = quote! {
+                let #param_ident = match params_list.get(#index) {
+                    Some(value) => match #decoded {
+                        Ok(val) => #wrap,
+                        Err(#err_pat) => {
+                            ctx.error(format!("Failed to parse parameter {} at position {}: {}", #param_name, #index, #err_detail));
+                            return Err(anyhow::anyhow!(format!("Failed to parse parameter {} at position {}: {}", #param_name, #index, #err_detail)));
+                        }
+                    },
+                    None => {
+                        ctx.error(format!("Missing parameter {} at position {}", #param_name, #index));
+                        return Err(anyhow::anyhow!(format!("Missing parameter {} at position {}", #param_name, #index)));
+                    }
+                };
+            };
+            extractions.extend(path_or_payload(
+                param_ident,
+                param_type,
+                extraction,
+                path_sourced,
+            ));
+        }
+
+        return extractions;
+    }
 
     // If there is only one parameter, deserialize the entire input into that type directly.
-    if params.len() == 1 {
+    // A paginated payload also carries the window, so its parameters are always read by key.
+    if params.len() == 1 && !action_impl.paginates() {
         let (param_ident, param_type) = &params[0];
-        extractions.extend(quote! {
+        let type_str = quote! { #param_type }.to_string();
+        if is_cow_str_type(&type_str) {
+            // Cow<str> can't be deserialized into a borrowed value, so read an owned String
+            let decoded = action_impl.decode_tokens(quote! { params_value }, &quote! { String });
+            extractions.extend(quote! {
+                let #param_ident = match #decoded {
+                    Ok(val) => std::borrow::Cow::Owned(val),
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameter for single-parameter action: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameter for single-parameter action: {}", #err_detail)));
+                    }
+                };
+            });
+            return extractions;
+        }
+        let decoded = action_impl.decode_tokens(quote! { params_value }, &quote! { #param_type });
+        let extraction = quote! {
             // For single-parameter actions, deserialize the whole payload into the parameter type.
-            let #param_ident: #param_type = match params_value.as_type::<#param_type>() {
+            let #param_ident: #param_type = match #decoded {
                 Ok(val) => val,
-                Err(err) => {
-                    ctx.error(format!("Failed to parse parameter for single-parameter action: {}", err));
-                    return Err(anyhow!(format!("Failed to parse parameter for single-parameter action: {}", err)));
+                Err(#err_pat) => {
+                    ctx.error(format!("Failed to parse parameter for single-parameter action: {}", #err_detail));
+                    return Err(anyhow::anyhow!(format!("Failed to parse parameter for single-parameter action: {}", #err_detail)));
+                }
+            };
+        };
+        extractions.extend(path_or_payload(
+            param_ident,
+            param_type,
+            extraction,
+            path_sourced,
+        ));
+        return extractions;
+    }
+
+    // If all parameters are primitives, read the map once and extract each value by key
+    // instead of deserializing the whole map again for every parameter. A serializer decodes
+    // every parameter from its entry, so it always takes this path.
+    if action_impl.serializer.is_some()
+        || params
+            .iter()
+            .all(|(_, param_type)| is_primitive_param_type(&quote! { #param_type }.to_string()))
+    {
+        // Path-sourced parameters may leave the payload empty, so a missing map only
+        // fails for the parameters that are actually looked up in it
+        if !path_sourced.is_empty() {
+            extractions.extend(quote! {
+                let params_map = params_value
+                    .as_map_ref::<String, runar_common::types::ArcValueType>()
+                    .unwrap_or_default();
+            });
+        } else {
+            extractions.extend(quote! {
+            let params_map = match params_value.as_map_ref::<String, runar_common::types::ArcValueType>() {
+                Ok(map) => map,
+                Err(#err_pat) => {
+                    ctx.error(format!("Failed to parse parameters as map: {}", #err_detail));
+                    return Err(anyhow::anyhow!(format!("Failed to parse parameters as map: {}", #err_detail)));
                 }
             };
         });
+        }
+
+        for (param_ident, param_type) in params {
+            let param_name = param_ident.to_string();
+            let lookup = param_lookup(
+                quote! { params_map },
+                &param_name,
+                param_ident,
+                param_aliases,
+            );
+            let type_str = quote! { #param_type }.to_string();
+
+            // Cow<str> and &str are read as an owned String
+            let (value_type, wrap) = if is_cow_str_type(&type_str) {
+                (quote! { String }, quote! { std::borrow::Cow::Owned(val) })
+            } else if type_str == "& str" {
+                (quote! { String }, quote! { val })
+            } else {
+                (quote! { #param_type }, quote! { val })
+            };
+            let decoded = action_impl.decode_tokens(quote! { value.clone() }, &value_type);
+
+            let extraction = quote! {
+                let #param_ident = match #lookup {
+                    Some(value) => match #decoded {
+                        Ok(val) => #wrap,
+                        Err(#err_pat) => {
+                            ctx.error(format!("Failed to parse parameter {}: {}", #param_name, #err_detail));
+                            return Err(anyhow::anyhow!(format!("Failed to parse parameter {}: {}", #param_name, #err_detail)));
+                        }
+                    },
+                    None => {
+                        ctx.error(format!("Missing parameter {}", #param_name));
+                        return Err(anyhow::anyhow!(format!("Missing parameter {}", #param_name)));
+                    }
+                };
+            };
+            extractions.extend(path_or_payload(
+                param_ident,
+                param_type,
+                extraction,
+                path_sourced,
+            ));
+        }
+
         return extractions;
     }
 
     for (param_ident, param_type) in params {
         let param_name = param_ident.to_string();
+        let lookup = param_lookup(quote! { map }, &param_name, param_ident, param_aliases);
         let type_str = quote! { #param_type }.to_string();
 
         // Extract parameters based on their type
-        let extraction = if type_str.contains("f64") || type_str.contains("f32") {
+        let extraction = if vec_element_type(param_type).is_some() {
+            // Vec extraction - deserialize the list value directly
+            quote! {
+                let #param_ident = match params_value.as_map_ref::<String, runar_common::types::ArcValueType>() {
+                    Ok(map) => {
+                        match #lookup {
+                            Some(value) => match value.clone().as_type::<#param_type>() {
+                                Ok(val) => val,
+                                Err(#err_pat) => {
+                                    ctx.error(format!("Failed to parse parameter {} as a list: {}", #param_name, #err_detail));
+                                    return Err(anyhow::anyhow!(format!("Failed to parse parameter {} as a list: {}", #param_name, #err_detail)));
+                                }
+                            },
+                            None => {
+                                ctx.error(format!("Missing parameter {}", #param_name));
+                                return Err(anyhow::anyhow!(format!("Missing parameter {}", #param_name)));
+                            }
+                        }
+                    },
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameters as map: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameters as map: {}", #err_detail)));
+                    }
+                };
+            }
+        } else if map_key_value_types(param_type).is_some() {
+            // Map extraction - deserialize the map value directly, whatever its key type
+            quote! {
+                let #param_ident = match params_value.as_map_ref::<String, runar_common::types::ArcValueType>() {
+                    Ok(map) => {
+                        match #lookup {
+                            Some(value) => match value.clone().as_type::<#param_type>() {
+                                Ok(val) => val,
+                                Err(#err_pat) => {
+                                    ctx.error(format!("Failed to parse parameter {} as a map: {}", #param_name, #err_detail));
+                                    return Err(anyhow::anyhow!(format!("Failed to parse parameter {} as a map: {}", #param_name, #err_detail)));
+                                }
+                            },
+                            None => {
+                                ctx.error(format!("Missing parameter {}", #param_name));
+                                return Err(anyhow::anyhow!(format!("Missing parameter {}", #param_name)));
+                            }
+                        }
+                    },
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameters as map: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameters as map: {}", #err_detail)));
+                    }
+                };
+            }
+        } else if type_str == "f64" || type_str == "f32" {
             // Floating point extraction
             quote! {
                 let #param_ident = match params_value.as_map_ref::<String, f64>() {
                     Ok(map) => {
-                        match map.get(#param_name) {
+                        match #lookup {
                             Some(value) => *value,
                             None => {
                                 ctx.error(format!("Missing parameter {}", #param_name));
-                                return Err(anyhow!(format!("Missing parameter {}", #param_name)));
+                                return Err(anyhow::anyhow!(format!("Missing parameter {}", #param_name)));
                             }
                         }
                     },
-                    Err(err) => {
-                        ctx.error(format!("Failed to parse parameters as map with f64 values: {}", err));
-                        return Err(anyhow!(format!("Failed to parse parameters as map with f64 values: {}", err)));
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameters as map with f64 values: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameters as map with f64 values: {}", #err_detail)));
                     }
                 };
             }
-        } else if type_str.contains("i32") {
+        } else if type_str == "i32" {
             // Integer extraction (i32)
             quote! {
                 let #param_ident = match params_value.as_map_ref::<String, i32>() {
                     Ok(map) => {
-                        match map.get(#param_name) {
+                        match #lookup {
                             Some(value) => *value,
                             None => {
                                 ctx.error(format!("Missing parameter {}", #param_name));
-                                return Err(anyhow!(format!("Missing parameter {}", #param_name)));
+                                return Err(anyhow::anyhow!(format!("Missing parameter {}", #param_name)));
                             }
                         }
                     },
-                    Err(err) => {
-                        ctx.error(format!("Failed to parse parameters as map with i32 values: {}", err));
-                        return Err(anyhow!(format!("Failed to parse parameters as map with i32 values: {}", err)));
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameters as map with i32 values: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameters as map with i32 values: {}", #err_detail)));
                     }
                 };
             }
-        } else if type_str.contains("i64") {
+        } else if type_str == "i64" {
             // Integer extraction (i64)
             quote! {
                 let #param_ident = match params_value.as_map_ref::<String, i64>() {
                     Ok(map) => {
-                        match map.get(#param_name) {
+                        match #lookup {
                             Some(value) => *value,
                             None => {
                                 ctx.error(format!("Missing parameter {}", #param_name));
-                                return Err(anyhow!(format!("Missing parameter {}", #param_name)));
+                                return Err(anyhow::anyhow!(format!("Missing parameter {}", #param_name)));
+                            }
+                        }
+                    },
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameters as map with i64 values: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameters as map with i64 values: {}", #err_detail)));
+                    }
+                };
+            }
+        } else if is_cow_str_type(&type_str) {
+            // Cow<str> extraction, read as an owned String
+            quote! {
+                let #param_ident = match params_value.as_map_ref::<String, String>() {
+                    Ok(map) => {
+                        match #lookup {
+                            Some(value) => std::borrow::Cow::Owned(value.clone()),
+                            None => {
+                                ctx.error(format!("Missing parameter {}", #param_name));
+                                return Err(anyhow::anyhow!(format!("Missing parameter {}", #param_name)));
                             }
                         }
                     },
-                    Err(err) => {
-                        ctx.error(format!("Failed to parse parameters as map with i64 values: {}", err));
-                        return Err(anyhow!(format!("Failed to parse parameters as map with i64 values: {}", err)));
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameters as map with String values: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameters as map with String values: {}", #err_detail)));
                     }
                 };
             }
-        } else if type_str.contains("String") || type_str.contains("&str") {
+        } else if type_str == "String" || type_str == "& str" {
             // String extraction
             quote! {
                 let #param_ident = match params_value.as_map_ref::<String, String>() {
                     Ok(map) => {
-                        match map.get(#param_name) {
+                        match #lookup {
                             Some(value) => value.clone(),
                             None => {
                                 ctx.error(format!("Missing parameter {}", #param_name));
-                                return Err(anyhow!(format!("Missing parameter {}", #param_name)));
+                                return Err(anyhow::anyhow!(format!("Missing parameter {}", #param_name)));
                             }
                         }
                     },
-                    Err(err) => {
-                        ctx.error(format!("Failed to parse parameters as map with String values: {}", err));
-                        return Err(anyhow!(format!("Failed to parse parameters as map with String values: {}", err)));
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameters as map with String values: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameters as map with String values: {}", #err_detail)));
                     }
                 };
             }
-        } else if type_str.contains("bool") {
+        } else if type_str == "bool" {
             // Boolean extraction
             quote! {
                 let #param_ident = match params_value.as_map_ref::<String, bool>() {
                     Ok(map) => {
-                        match map.get(#param_name) {
+                        match #lookup {
                             Some(value) => *value,
                             None => {
                                 ctx.error(format!("Missing parameter {}", #param_name));
-                                return Err(anyhow!(format!("Missing parameter {}", #param_name)));
+                                return Err(anyhow::anyhow!(format!("Missing parameter {}", #param_name)));
                             }
                         }
                     },
-                    Err(err) => {
-                        ctx.error(format!("Failed to parse parameters as map with bool values: {}", err));
-                        return Err(anyhow!(format!("Failed to parse parameters as map with bool values: {}", err)));
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameters as map with bool values: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameters as map with bool values: {}", #err_detail)));
                     }
                 };
             }
         } else {
-            // Complex type (struct) extraction - attempt to deserialize
+            // Complex type (struct, enum) extraction - deserialize the entry's own value, so
+            // types such as untagged enums see the raw value rather than a nested lookup
             quote! {
                 let #param_ident = match params_value.as_map_ref::<String, runar_common::types::ArcValueType>() {
                     Ok(map) => {
-                        match map.get(#param_name) {
+                        match #lookup {
                             Some(value) => {
-                                match value.as_type::<#param_type>() {
+                                match value.clone().as_type::<#param_type>() {
                                     Ok(val) => val,
-                                    Err(err) => {
-                                        ctx.error(format!("Failed to parse parameter {}: {}", #param_name, err));
-                                        return Err(anyhow!(format!("Failed to parse parameter {}: {}", #param_name, err)));
+                                    Err(#err_pat) => {
+                                        ctx.error(format!("Failed to parse parameter {}: {}", #param_name, #err_detail));
+                                        return Err(anyhow::anyhow!(format!("Failed to parse parameter {}: {}", #param_name, #err_detail)));
                                     }
                                 }
                             },
                             None => {
                                 ctx.error(format!("Missing parameter {}", #param_name));
-                                return Err(anyhow!(format!("Missing parameter {}", #param_name)));
+                                return Err(anyhow::anyhow!(format!("Missing parameter {}", #param_name)));
                             }
                         }
                     },
-                    Err(err) => {
-                        ctx.error(format!("Failed to parse parameters as map: {}", err));
-                        return Err(anyhow!(format!("Failed to parse parameters as map: {}", err)));
+                    Err(#err_pat) => {
+                        ctx.error(format!("Failed to parse parameters as map: {}", #err_detail));
+                        return Err(anyhow::anyhow!(format!("Failed to parse parameters as map: {}", #err_detail)));
                     }
                 };
             }
         };
 
-        extractions.extend(extraction);
+        extractions.extend(path_or_payload(
+            param_ident,
+            param_type,
+            extraction,
+            path_sourced,
+        ));
     }
 
     extractions
 }
 
+/// Read a `#[from_path_or_payload]` parameter from the request's path captures, falling back
+/// to its payload extraction when the route didn't capture it
+fn path_or_payload(
+    param_ident: &Ident,
+    param_type: &Type,
+    extraction: TokenStream2,
+    path_sourced: &[Ident],
+) -> TokenStream2 {
+    if !path_sourced.contains(param_ident) {
+        return extraction;
+    }
+    let param_name = param_ident.to_string();
+    quote! {
+        let #param_ident: #param_type = match ctx.path_params.get(#param_name) {
+            Some(captured) => match captured.parse::<#param_type>() {
+                Ok(val) => val,
+                Err(err) => {
+                    ctx.error(format!("Failed to parse path parameter {}: {}", #param_name, err));
+                    return Err(anyhow::anyhow!(format!("Failed to parse path parameter {}: {}", #param_name, err)));
+                }
+            },
+            None => {
+                #extraction
+                #param_ident
+            }
+        };
+    }
+}
+
+/// Look a parameter up in the payload map by its name, then by each of its aliases
+fn param_lookup(
+    map: TokenStream2,
+    param_name: &str,
+    param_ident: &Ident,
+    param_aliases: &HashMap<Ident, Vec<LitStr>>,
+) -> TokenStream2 {
+    let fallbacks = param_aliases
+        .get(param_ident)
+        .into_iter()
+        .flatten()
+        .map(|alias| quote! { .or_else(|| #map.get(#alias)) });
+    quote! { #map.get(#param_name) #(#fallbacks)* }
+}
+
 /// Generate method call with extracted parameters
 fn generate_method_call(fn_ident: &Ident, params: &[(Ident, Type)]) -> TokenStream2 {
-    let param_idents = params.iter().map(|(ident, _)| {
-        quote! { #ident }
+    let param_idents = params.iter().map(|(ident, ty)| {
+        // &str parameters are extracted as an owned String
+        if quote! { #ty }.to_string() == "& str" {
+            quote! { &#ident }
+        } else {
+            quote! { #ident }
+        }
     });
 
     quote! {
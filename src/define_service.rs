@@ -0,0 +1,117 @@
+// Declarative service macro implementation
+//
+// This module implements the define_service macro, which declares a service
+// struct and its actions in a single block and expands into the same code as
+// a struct with a #[service] impl block of #[action] methods.
+
+use crate::utils::is_macro_attribute;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input, Error, Ident, ImplItemFn, LitStr, Result, Token, Visibility,
+};
+
+// Define a struct to parse the `key: value` entries of the macro input
+pub struct DefineServiceImpl {
+    /// Visibility of the generated struct
+    pub vis: Visibility,
+    /// Name of the generated struct
+    pub service: Ident,
+    /// Service attributes passed on to #[service] (name, path, description, version)
+    pub attributes: Vec<(Ident, LitStr)>,
+    /// Action methods of the service
+    pub actions: Vec<ImplItemFn>,
+}
+
+impl Parse for DefineServiceImpl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut service = None;
+        let mut attributes = Vec::new();
+        let mut actions = None;
+
+        while !input.is_empty() {
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![:]>()?;
+
+            if key == "service" {
+                let vis = input.parse::<Visibility>()?;
+                service = Some((vis, input.parse::<Ident>()?));
+            } else if key == "actions" {
+                let content;
+                bracketed!(content in input);
+                let mut methods = Vec::new();
+                while !content.is_empty() {
+                    methods.push(content.parse::<ImplItemFn>()?);
+                    // Commas between actions are optional
+                    if content.peek(Token![,]) {
+                        content.parse::<Token![,]>()?;
+                    }
+                }
+                actions = Some(methods);
+            } else if key == "name" || key == "path" || key == "description" || key == "version" {
+                attributes.push((key, input.parse::<LitStr>()?));
+            } else {
+                return Err(Error::new_spanned(
+                    &key,
+                    "Unknown define_service key, expected one of: service, name, path, description, version, actions",
+                ));
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        let Some((vis, service)) = service else {
+            return Err(Error::new(
+                input.span(),
+                "Expected `service: StructName` naming the service struct",
+            ));
+        };
+
+        Ok(DefineServiceImpl {
+            vis,
+            service,
+            attributes,
+            actions: actions.unwrap_or_default(),
+        })
+    }
+}
+
+/// Implementation of the define_service macro
+pub fn define_service_macro(input: TokenStream) -> TokenStream {
+    let define_service = parse_macro_input!(input as DefineServiceImpl);
+    let vis = &define_service.vis;
+    let service = &define_service.service;
+    let attribute_keys = define_service.attributes.iter().map(|(key, _)| key);
+    let attribute_values = define_service.attributes.iter().map(|(_, value)| value);
+
+    // Every method is an action, methods carrying their own #[action(...)] keep its options
+    let actions = define_service.actions.iter().map(|method| {
+        let has_action_attribute = method
+            .attrs
+            .iter()
+            .any(|attr| is_macro_attribute(attr, "action"));
+        if has_action_attribute {
+            quote! { #method }
+        } else {
+            quote! {
+                #[runar_macros::action]
+                #method
+            }
+        }
+    });
+
+    TokenStream::from(quote! {
+        #[derive(Clone)]
+        #vis struct #service;
+
+        #[runar_macros::service(#(#attribute_keys = #attribute_values),*)]
+        impl #service {
+            #(#actions)*
+        }
+    })
+}
@@ -5,10 +5,14 @@
 extern crate proc_macro;
 
 mod action;
+mod define_service;
 mod publish;
 mod service;
 mod subscribe;
+#[cfg(feature = "node_implementation")]
+mod test_node;
 mod utils;
+mod vmap;
 
 use proc_macro::TokenStream;
 
@@ -47,3 +51,44 @@ pub fn subscribe(attr: TokenStream, item: TokenStream) -> TokenStream {
 pub fn publish(attr: TokenStream, item: TokenStream) -> TokenStream {
     publish::publish_macro(attr, item)
 }
+
+/// Value map macro for building ArcValueType maps
+///
+/// This macro builds a `runar_common::types::ArcValueType` map from `key => value`
+/// pairs. Primitive values are wrapped with `new_primitive` and braced groups of
+/// pairs become nested maps.
+#[proc_macro]
+pub fn vmap_arc(input: TokenStream) -> TokenStream {
+    vmap::vmap_arc_macro(input)
+}
+
+/// Value list macro for building ArcValueType lists
+///
+/// This macro builds a `runar_common::types::ArcValueType` list from its elements.
+/// Primitive values are wrapped with `new_primitive`, braced groups of `key => value`
+/// pairs become nested maps and bracketed groups become nested lists.
+#[proc_macro]
+pub fn varr_arc(input: TokenStream) -> TokenStream {
+    vmap::varr_arc_macro(input)
+}
+
+/// Declarative service macro for action-only services
+///
+/// This macro declares a unit service struct and its actions in one block and
+/// expands into the struct plus a `#[service]` impl block whose methods are
+/// all registered as actions.
+#[proc_macro]
+pub fn define_service(input: TokenStream) -> TokenStream {
+    define_service::define_service_macro(input)
+}
+
+/// Test node macro for integration test setup
+///
+/// This macro expands, inside an async context, into a started node with
+/// networking disabled and debug logging, after adding each of the given
+/// services: `let node = test_node!(MathService::new(), EchoService::new());`.
+#[cfg(feature = "node_implementation")]
+#[proc_macro]
+pub fn test_node(input: TokenStream) -> TokenStream {
+    test_node::test_node_macro(input)
+}
@@ -4,49 +4,124 @@
 // the result of an action to a specified topic.
 
 use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
 use syn::{
-    parse::Parse, parse::ParseStream, parse_macro_input, Expr, ItemFn, Lit, LitStr, Meta, Result,
-    Token,
+    parse::Parse, parse::ParseStream, parse_macro_input, punctuated::Punctuated, Error, Expr,
+    ExprLit, ItemFn, Lit, LitInt, LitStr, Meta, Result, Token,
 };
 
 // Define a struct to parse the macro attributes
 pub struct PublishImpl {
-    pub path: LitStr,
+    /// Fixed topic the result is published to
+    pub path: Option<LitStr>,
+    /// Closure computing the topics to publish to from the result
+    pub paths_from: Option<Expr>,
+    /// Delay before the event is delivered, in milliseconds
+    pub delay_ms: Option<LitInt>,
+    /// Expression published instead of the action's result
+    pub value: Option<Expr>,
 }
 
 impl Parse for PublishImpl {
     fn parse(input: ParseStream) -> Result<Self> {
-        // Check if we have path="value" format
-        if input.peek(syn::Ident) {
-            let meta = input.parse::<Meta>()?;
-            if let Meta::NameValue(name_value) = meta {
-                if name_value.path.is_ident("path") {
-                    // Extract the string literal from the expression
-                    if let Expr::Lit(expr_lit) = &name_value.value {
-                        if let Lit::Str(lit_str) = &expr_lit.lit {
-                            return Ok(PublishImpl {
-                                path: lit_str.clone(),
-                            });
-                        }
+        if input.is_empty() {
+            return Err(Error::new(
+                Span::call_site(),
+                "Expected path=\"value\" or a string literal",
+            ));
+        }
+
+        let mut path = None;
+        let mut paths_from = None;
+        let mut delay_ms = None;
+        let mut value = None;
+
+        // A leading string literal is the path, optionally followed by more options
+        if input.peek(LitStr) {
+            path = Some(input.parse::<LitStr>()?);
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+        for meta in metas {
+            let Meta::NameValue(name_value) = meta else {
+                return Err(Error::new_spanned(
+                    meta,
+                    "Expected path=\"value\" or a string literal",
+                ));
+            };
+
+            if name_value.path.is_ident("path") {
+                // Extract the string literal from the expression
+                match &name_value.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit_str),
+                        ..
+                    }) => path = Some(lit_str.clone()),
+                    other => {
+                        return Err(Error::new_spanned(
+                            other,
+                            "Expected a string literal for the publish path",
+                        ))
                     }
                 }
+            } else if name_value.path.is_ident("paths_from") {
+                paths_from = Some(name_value.value);
+            } else if name_value.path.is_ident("delay_ms") {
+                match &name_value.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Int(lit_int),
+                        ..
+                    }) => {
+                        lit_int.base10_parse::<u64>()?;
+                        delay_ms = Some(lit_int.clone());
+                    }
+                    other => {
+                        return Err(Error::new_spanned(
+                            other,
+                            "Expected an integer literal for delay_ms",
+                        ))
+                    }
+                }
+            } else if name_value.path.is_ident("value") {
+                value = Some(name_value.value);
+            } else {
+                return Err(Error::new_spanned(
+                    &name_value.path,
+                    "Unknown publish option, expected one of: path, paths_from, delay_ms, value",
+                ));
             }
-            return Err(input.error("Expected path=\"value\" or a string literal"));
         }
 
-        // Otherwise, try to parse as a string literal followed by a handler
-        let path = input.parse::<LitStr>()?;
-
-        // Check if we have a handler
-        // if input.peek(Token![,]) {
-        //     input.parse::<Token![,]>()?;
-        //     let handler = input.parse::<Expr>()?;
-        //     Ok(PublishImpl { path, handler: Some(handler) })
-        // } else {
-        // Just a path string
-        Ok(PublishImpl { path })
-        // }
+        match (&path, &paths_from) {
+            (None, None) => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "Expected path=\"value\" or a string literal",
+                ))
+            }
+            (Some(path), Some(_)) => {
+                return Err(Error::new_spanned(
+                    path,
+                    "path and paths_from can't be combined, use one or the other",
+                ))
+            }
+            // Never fall back to a default topic
+            (Some(path), None) if path.value().trim().is_empty() => {
+                return Err(Error::new_spanned(path, "Publish path cannot be empty"));
+            }
+            _ => {}
+        }
+
+        Ok(PublishImpl {
+            path,
+            paths_from,
+            delay_ms,
+            value,
+        })
     }
 }
 
@@ -57,7 +132,6 @@ pub fn publish_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     // Parse the attributes
     let publish_impl = parse_macro_input!(attr as PublishImpl);
-    let path = &publish_impl.path;
 
     // Get the function body
     let attrs = &input.attrs;
@@ -65,6 +139,87 @@ pub fn publish_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     let sig = &input.sig;
     let block = &input.block;
 
+    // Publish the result, or the value expression evaluated before the body runs so it can
+    // read the action's inputs
+    let (value_binding, published_value) = match &publish_impl.value {
+        Some(value) => (
+            quote! { let published_value = #value; },
+            quote! { published_value },
+        ),
+        None => (TokenStream2::new(), quote! { action_result.clone() }),
+    };
+    // Only paths_from reads the result when a value is published
+    let ok_pattern = match (&publish_impl.value, &publish_impl.paths_from) {
+        (Some(_), None) => quote! { Ok(_) },
+        _ => quote! { Ok(ref action_result) },
+    };
+
+    // Publish right away, or from a task that waits for the delay first
+    let publish_result = match (&publish_impl.path, &publish_impl.delay_ms) {
+        (None, delay_ms) => {
+            let paths_from = &publish_impl.paths_from;
+            let deliver = match delay_ms {
+                Some(delay_ms) => quote! {
+                    // Schedule the result for delivery to every topic after the delay
+                    if !topics.is_empty() {
+                        let delayed_ctx = ctx.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(std::time::Duration::from_millis(#delay_ms)).await;
+                            for topic in topics {
+                                if let Err(e) = delayed_ctx.publish(&topic, Some(payload.clone())).await {
+                                    delayed_ctx.error(format!("Failed to publish delayed result to {}: {}", topic, e));
+                                }
+                            }
+                        });
+                    }
+                },
+                None => quote! {
+                    // Publish the result to every topic
+                    for topic in topics {
+                        if let Err(e) = ctx.publish(&topic, Some(payload.clone())).await {
+                            ctx.error(format!("Failed to publish result to {}: {}", topic, e));
+                        }
+                    }
+                },
+            };
+            quote! {
+                // The expected signature lets the closure's parameter type be inferred
+                fn publish_topics<T, I>(result: &T, paths_from: impl FnOnce(&T) -> I) -> Vec<String>
+                where
+                    I: IntoIterator,
+                    I::Item: Into<String>,
+                {
+                    paths_from(result).into_iter().map(Into::into).collect()
+                }
+
+                // Compute the topics from the result, an empty list publishes nothing
+                let topics = publish_topics(action_result, #paths_from);
+                let payload = runar_common::types::ArcValueType::from_struct(#published_value);
+                #deliver
+            }
+        }
+        (Some(path), Some(delay_ms)) => quote! {
+            // Schedule the result for delivery after the delay
+            let delayed_ctx = ctx.clone();
+            let payload = runar_common::types::ArcValueType::from_struct(#published_value);
+            tokio::spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_millis(#delay_ms)).await;
+                if let Err(e) = delayed_ctx.publish(#path, Some(payload)).await {
+                    delayed_ctx.error(format!("Failed to publish delayed result to {}: {}", #path, e));
+                }
+            });
+        },
+        (Some(path), None) => quote! {
+            // Publish the result to the specified topic
+            match ctx.publish(#path, Some(runar_common::types::ArcValueType::from_struct(#published_value))).await {
+                Ok(_) => {},
+                Err(e) => {
+                    ctx.error(format!("Failed to publish result to {}: {}", #path, e));
+                }
+            }
+        },
+    };
+
     // Check if the function is already async
     let is_async = input.sig.asyncness.is_some();
 
@@ -73,18 +228,14 @@ pub fn publish_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
         quote! {
             #(#attrs)*
             #vis #sig {
+                #value_binding
+
                 // Execute the original function body
                 let result = #block;
 
                 // If the result is Ok, publish it
-                if let Ok(ref action_result) = &result {
-                    // Publish the result to the specified topic
-                    match ctx.publish(#path, Some(runar_common::types::ArcValueType::from_struct(action_result.clone()))).await {
-                        Ok(_) => {},
-                        Err(e) => {
-                            ctx.error(format!("Failed to publish result to {}: {}", #path, e));
-                        }
-                    }
+                if let #ok_pattern = &result {
+                    #publish_result
                 }
 
                 // Return the original result
@@ -95,18 +246,14 @@ pub fn publish_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
         quote! {
             #(#attrs)*
             #vis async #sig {
+                #value_binding
+
                 // Execute the original function body
                 let result = (|| #block)();
 
                 // If the result is Ok, publish it
-                if let Ok(ref action_result) = &result {
-                    // Publish the result to the specified topic
-                    match ctx.publish(#path, Some(runar_common::types::ArcValueType::from_struct(action_result.clone()))).await {
-                        Ok(_) => {},
-                        Err(e) => {
-                            ctx.error(format!("Failed to publish result to {}: {}", #path, e));
-                        }
-                    }
+                if let #ok_pattern = &result {
+                    #publish_result
                 }
 
                 // Return the original result
@@ -4,133 +4,2112 @@
 // of a Runar service by automatically implementing the AbstractService trait and
 // handling action registration.
 
+use crate::action::{parse_action_attribute, ActionImpl};
+use crate::publish::PublishImpl;
+use crate::subscribe::SubscribeImpl;
+use crate::utils::{
+    expect_lit_bool, extract_result_ok_type, has_marker, is_bytes_type, is_cow_str_type,
+    is_macro_attribute, is_primitive_param_type, map_key_value_types, parameter_aliases,
+    strip_markers, vec_element_type, PARAMETER_MARKERS,
+};
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
-use quote::{format_ident, quote};
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, quote_spanned};
 use std::collections::{HashMap, HashSet};
+use syn::ext::IdentExt;
+use syn::parse::{ParseStream, Parser};
+use syn::spanned::Spanned;
 use syn::{
-    parse_macro_input, parse_quote, Attribute, FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, Lit,
-    LitStr, Meta, Pat, PatType, ReturnType, Type, TypePath,
+    parse_macro_input, parse_quote, punctuated::Punctuated, token::Comma, Attribute, Expr, ExprLit,
+    Fields, FnArg, Ident, ImplItem, ImplItemFn, ItemEnum, ItemImpl, ItemStruct, Lit, LitStr, Meta,
+    MetaNameValue, Pat, PatType, ReturnType, Token, Type, TypePath,
 };
 
-/// Implementation of the service macro
-pub fn service_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
-    // Parse the input as a struct
-    let input = parse_macro_input!(item as ItemImpl);
+/// Implementation of the service macro
+pub fn service_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
+    // An enum wrapping one service per variant delegates to the active variant
+    if let Ok(item_enum) = syn::parse::<ItemEnum>(item.clone()) {
+        return match generate_enum_service(attr, &item_enum) {
+            Ok(service_impl) => TokenStream::from(quote! {
+                #item_enum
+
+                #service_impl
+            }),
+            Err(err) => err.to_compile_error().into(),
+        };
+    }
+
+    // A struct only takes the options generated from its fields, the impl block carries the service
+    if let Ok(item_struct) = syn::parse::<ItemStruct>(item.clone()) {
+        return match generate_struct_items(attr, &item_struct) {
+            Ok(accessors) => TokenStream::from(quote! {
+                #item_struct
+
+                #accessors
+            }),
+            Err(err) => err.to_compile_error().into(),
+        };
+    }
+
+    // Parse the input as a struct
+    let mut input = parse_macro_input!(item as ItemImpl);
+
+    // Extract the struct name
+    let struct_type = match &*input.self_ty {
+        Type::Path(TypePath { ref path, .. }) => path.segments.last().unwrap().ident.clone(),
+        other => {
+            return syn::Error::new_spanned(
+                other,
+                "#[service] can only be applied to the impl block of a named struct, or to an enum of services",
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+
+    // Extract the service attributes from the macro annotation
+    let service_attrs = match extract_service_attributes(attr) {
+        Ok(attrs) => attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Merge the service-wide action defaults into each #[action] attribute
+    apply_action_defaults(&mut input, &service_attrs.action_defaults);
+
+    // Prefix the relative #[subscribe] and #[publish] topics with the service namespace
+    if let Some(namespace) = service_attrs.get("namespace") {
+        apply_topic_namespace(&mut input, namespace);
+    }
+
+    // Collect the #[on_warmup] hooks, which are markers rather than macros
+    let warmup_hooks = take_warmup_hooks(&mut input);
+    if let (false, Some((_, marker))) = (service_attrs.flag("warmup"), warmup_hooks.first()) {
+        return syn::Error::new_spanned(marker, "#[on_warmup] requires #[service(warmup = true)]")
+            .to_compile_error()
+            .into();
+    }
+
+    // Find all methods marked with #[action] or #[subscribe]
+    let all_methods = collect_action_methods(&input);
+
+    // Warn about subscriptions and published topics without a counterpart if requested
+    let topic_check = if service_attrs.flag("check_topics") {
+        generate_topic_check(&input, &service_attrs)
+    } else {
+        TokenStream2::new()
+    };
+
+    // Generate the service metadata
+    let service_metadata = generate_service_metadata();
+
+    // Assert the service is Send + Sync, so a non-thread-safe field is reported on the service type
+    let send_sync_assertion = generate_send_sync_assertion(&struct_type);
+
+    // Generate the trait implementation for the AbstractService trait
+    let service_impl = generate_abstract_service_impl(&struct_type, &all_methods, &service_attrs);
+
+    // Generate the action name and path constants and the annotations map
+    let action_constants =
+        match generate_action_constants(&struct_type, &all_methods, &service_attrs) {
+            Ok(constants) => constants,
+            Err(err) => return err.to_compile_error().into(),
+        };
+
+    // Generate the typed params structs for actions with params_struct = true
+    let params_structs = generate_params_structs(&all_methods);
+
+    // Generate the typed event emitter for the topics published with #[publish]
+    let events_emitter = generate_events_emitter(&struct_type, &input);
+
+    // Generate the built-in health action if requested
+    let health_action = if service_attrs.flag("health") {
+        // The built-in action would be registered on top of a user-defined one
+        if let Some((fn_ident, ..)) = dispatched_actions(&all_methods)
+            .into_iter()
+            .find(|(.., action_path)| action_path == "health")
+        {
+            return syn::Error::new_spanned(
+                fn_ident,
+                "health = true registers a built-in `health` action, which this action's path collides with",
+            )
+            .to_compile_error()
+            .into();
+        }
+        generate_health_action(&struct_type)
+    } else {
+        TokenStream2::new()
+    };
+
+    // Generate the warmup method running the #[on_warmup] hooks if requested
+    let warmup_method = if service_attrs.flag("warmup") {
+        generate_warmup_method(&struct_type, &warmup_hooks)
+    } else {
+        TokenStream2::new()
+    };
+
+    // Generate the dispatcher over all actions, shared by static dispatch and JSON-RPC
+    let dispatcher = if service_attrs.flag("static_dispatch") || service_attrs.flag("jsonrpc") {
+        generate_dispatcher(&struct_type, &all_methods)
+    } else {
+        TokenStream2::new()
+    };
+
+    // Route paths with a match if requested
+    let static_dispatch = if service_attrs.flag("static_dispatch") {
+        generate_static_dispatch(&struct_type)
+    } else {
+        TokenStream2::new()
+    };
+
+    // Generate the JSON-RPC dispatcher over all actions if requested
+    let jsonrpc_dispatcher = if service_attrs.flag("jsonrpc") {
+        generate_jsonrpc_dispatcher(&struct_type, &all_methods)
+    } else {
+        TokenStream2::new()
+    };
+
+    // Generate the shared action trait and its impl for the service if requested
+    let action_trait = match generate_action_trait(&struct_type, &all_methods, &service_attrs) {
+        Ok(action_trait) => action_trait,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Generate the serialization round-trip test for the service's types if requested
+    let type_round_trip_test = if service_attrs.flag("verify_types") {
+        generate_type_round_trip_test(&struct_type, &all_methods)
+    } else {
+        TokenStream2::new()
+    };
+
+    // Return the input struct unchanged along with the trait implementation
+    TokenStream::from(quote! {
+        #input
+
+        #service_metadata
+
+        #send_sync_assertion
+
+        #service_impl
+
+        #action_constants
+
+        #params_structs
+
+        #events_emitter
+
+        #health_action
+
+        #warmup_method
+
+        #dispatcher
+
+        #jsonrpc_dispatcher
+
+        #static_dispatch
+
+        #type_round_trip_test
+
+        #action_trait
+
+        #topic_check
+    })
+}
+
+/// Generate an AbstractService impl for an enum that delegates to the service in the active variant
+///
+/// Each variant wraps exactly one service, e.g. `Local(LocalBackend)`. The metadata, actions and
+/// lifecycle all come from the wrapped service, so the enum takes no service attributes.
+fn generate_enum_service(attr: TokenStream, item_enum: &ItemEnum) -> syn::Result<TokenStream2> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new_spanned(
+            TokenStream2::from(attr),
+            "Service attributes aren't supported on enums, the wrapped services provide the metadata",
+        ));
+    }
+    if item_enum.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_enum.ident,
+            "Service enums need at least one variant wrapping a service",
+        ));
+    }
+
+    let mut variant_idents = Vec::new();
+    for variant in &item_enum.variants {
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                variant_idents.push(&variant.ident);
+            }
+            _ => return Err(syn::Error::new_spanned(
+                variant,
+                "Service enum variants must wrap exactly one service, e.g. `Local(LocalBackend)`",
+            )),
+        }
+    }
+
+    let enum_type = &item_enum.ident;
+    let (impl_generics, ty_generics, where_clause) = item_enum.generics.split_for_impl();
+    let delegate = |call: TokenStream2| {
+        quote! {
+            match self {
+                #(Self::#variant_idents(service) => runar_node::services::abstract_service::AbstractService::#call,)*
+            }
+        }
+    };
+    let name = delegate(quote! { name(service) });
+    let path = delegate(quote! { path(service) });
+    let description = delegate(quote! { description(service) });
+    let version = delegate(quote! { version(service) });
+    let network_id = delegate(quote! { network_id(service) });
+    let init = delegate(quote! { init(service, context).await });
+    let start = delegate(quote! { start(service, context).await });
+    let stop = delegate(quote! { stop(service, context).await });
+
+    Ok(quote! {
+        #[async_trait::async_trait]
+        impl #impl_generics runar_node::services::abstract_service::AbstractService for #enum_type #ty_generics #where_clause {
+            fn name(&self) -> &str {
+                #name
+            }
+
+            fn path(&self) -> &str {
+                #path
+            }
+
+            fn description(&self) -> &str {
+                #description
+            }
+
+            fn version(&self) -> &str {
+                #version
+            }
+
+            fn network_id(&self) -> Option<String> {
+                #network_id
+            }
+
+            async fn init(&self, context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                #init
+            }
+
+            async fn start(&self, context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                #start
+            }
+
+            async fn stop(&self, context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                #stop
+            }
+        }
+    })
+}
+
+/// Generate the items of `#[service(...)]` on the service struct, the field accessors of
+/// `expose` and the `Clone` impl of `derive_clone`
+fn generate_struct_items(attr: TokenStream, item_struct: &ItemStruct) -> syn::Result<TokenStream2> {
+    let metas = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse(attr)?;
+    let mut exposed = Vec::new();
+    let mut derive_clone = false;
+    for meta in &metas {
+        if meta.path.is_ident("derive_clone") {
+            derive_clone = match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Bool(flag),
+                    ..
+                }) => flag.value,
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "Expected a boolean literal for derive_clone",
+                    ))
+                }
+            };
+            continue;
+        }
+        if !meta.path.is_ident("expose") {
+            return Err(syn::Error::new_spanned(
+                &meta.path,
+                "On a struct, #[service] only takes expose and derive_clone, the service options go on the impl block",
+            ));
+        }
+        match &meta.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(field),
+                ..
+            }) => exposed.push(field.clone()),
+            Expr::Array(fields) => {
+                for field in &fields.elems {
+                    match field {
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Str(field),
+                            ..
+                        }) => exposed.push(field.clone()),
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "Expected a field name string literal",
+                            ))
+                        }
+                    }
+                }
+            }
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "Expected a field name, e.g. expose = \"storage\"",
+                ))
+            }
+        }
+    }
+
+    let accessors = if exposed.is_empty() {
+        TokenStream2::new()
+    } else {
+        generate_field_accessors(&exposed, item_struct)?
+    };
+    let clone_impl = if derive_clone {
+        generate_clone_impl(item_struct)?
+    } else {
+        TokenStream2::new()
+    };
+    Ok(quote! {
+        #accessors
+        #clone_impl
+    })
+}
+
+/// Generate the `Clone` impl of `#[service(derive_clone = true)]` on the service struct
+///
+/// Each field is cloned with its own `Clone` impl, a field type that isn't `Clone` is
+/// reported on the field. A `#[derive(Clone)]` on the struct would conflict with it and is
+/// reported here; a hand-written `impl Clone` isn't part of the macro input, so that conflict
+/// is left to rustc.
+fn generate_clone_impl(item_struct: &ItemStruct) -> syn::Result<TokenStream2> {
+    for attr in &item_struct.attrs {
+        if !attr.path().is_ident("derive") {
+            continue;
+        }
+        let derives = attr.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)?;
+        if let Some(clone) = derives
+            .iter()
+            .find(|path| path.segments.last().is_some_and(|seg| seg.ident == "Clone"))
+        {
+            return Err(syn::Error::new_spanned(
+                clone,
+                "derive_clone generates the Clone impl, remove Clone from the derive (a hand-written `impl Clone` for the struct would conflict too)",
+            ));
+        }
+    }
+
+    let struct_type = &item_struct.ident;
+    let field_types = item_struct.fields.iter().map(|field| &field.ty);
+    let body = match &item_struct.fields {
+        Fields::Named(fields) => {
+            let field_idents = fields.named.iter().map(|field| &field.ident);
+            quote! {
+                Self {
+                    #(#field_idents: Clone::clone(&self.#field_idents),)*
+                }
+            }
+        }
+        Fields::Unnamed(fields) => {
+            let indices = (0..fields.unnamed.len()).map(syn::Index::from);
+            quote! {
+                Self(#(Clone::clone(&self.#indices),)*)
+            }
+        }
+        Fields::Unit => quote! { Self },
+    };
+
+    let (impl_generics, ty_generics, where_clause) = item_struct.generics.split_for_impl();
+    let mut predicates = where_clause
+        .map(|where_clause| where_clause.predicates.iter().collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|predicate| quote! { #predicate })
+        .collect::<Vec<_>>();
+    predicates.extend(field_types.map(|field_type| {
+        quote_spanned! { field_type.span()=> #field_type: Clone }
+    }));
+    Ok(quote! {
+        impl #impl_generics Clone for #struct_type #ty_generics
+        where
+            #(#predicates,)*
+        {
+            fn clone(&self) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+/// Generate the accessors of `#[service(expose = "field")]` on the service struct
+///
+/// Each exposed field gets a `pub fn field(&self) -> &FieldType`, so other code can reach a
+/// wrapped client or store without the field being public. Several fields are exposed with
+/// `expose = ["storage", "client"]`.
+fn generate_field_accessors(
+    exposed: &[LitStr],
+    item_struct: &ItemStruct,
+) -> syn::Result<TokenStream2> {
+    let struct_type = &item_struct.ident;
+    let Fields::Named(fields) = &item_struct.fields else {
+        return Err(syn::Error::new_spanned(
+            struct_type,
+            "expose requires a struct with named fields",
+        ));
+    };
+
+    let mut accessors = Vec::new();
+    for name in exposed {
+        let Some(field) = fields.named.iter().find(|field| {
+            field
+                .ident
+                .as_ref()
+                .is_some_and(|ident| ident == &name.value())
+        }) else {
+            return Err(syn::Error::new_spanned(
+                name,
+                format!(
+                    "`{}` has no field `{}` to expose",
+                    struct_type,
+                    name.value()
+                ),
+            ));
+        };
+        let field_ident = &field.ident;
+        let field_type = &field.ty;
+        let doc = format!("Access the `{}` field of the service", name.value());
+        accessors.push(quote! {
+            #[doc = #doc]
+            pub fn #field_ident(&self) -> &#field_type {
+                &self.#field_ident
+            }
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = item_struct.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #struct_type #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    })
+}
+
+/// Attributes passed to the service macro
+struct ServiceAttributes {
+    /// Literal `key = value` pairs (strings, integers and booleans)
+    values: HashMap<String, String>,
+    /// Expression holding the service configuration (`config = MY_SERVICE_CONFIG`)
+    config: Option<Expr>,
+    /// Action options applied to every action that doesn't set them (`action_defaults(...)`)
+    action_defaults: Vec<Meta>,
+    /// Topics published outside the service, for `check_topics` (`external_topics = [...]`)
+    external_topics: Vec<LitStr>,
+}
+
+impl ServiceAttributes {
+    /// Get the literal value of an attribute
+    fn get(&self, key: &str) -> Option<&String> {
+        self.values.get(key)
+    }
+
+    /// Check whether a boolean attribute such as `health = true` is enabled
+    fn flag(&self, key: &str) -> bool {
+        self.values.get(key).is_some_and(|value| value == "true")
+    }
+}
+
+/// Service attributes holding a string or integer literal
+const SERVICE_VALUE_OPTIONS: &[&str] = &[
+    "name",
+    "path",
+    "description",
+    "version",
+    "namespace",
+    "trait",
+    "impl_trait",
+];
+
+/// Service attributes switching a feature on with a boolean literal
+const SERVICE_FLAG_OPTIONS: &[&str] = &[
+    "check_topics",
+    "health",
+    "warmup",
+    "static_dispatch",
+    "jsonrpc",
+    "verify_types",
+    "singleton",
+];
+
+/// Extract service attributes from the TokenStream
+fn extract_service_attributes(attr: TokenStream) -> syn::Result<ServiceAttributes> {
+    let mut attrs = ServiceAttributes {
+        values: HashMap::new(),
+        config: None,
+        action_defaults: Vec::new(),
+        external_topics: Vec::new(),
+    };
+
+    if attr.is_empty() {
+        return Ok(attrs);
+    }
+
+    // Parse the comma separated list of name = value pairs
+    let parse_metas = |input: ParseStream| {
+        let mut metas = Vec::new();
+        while !input.is_empty() {
+            // `trait` is a keyword, which a Meta path doesn't accept
+            if input.peek(Token![trait]) {
+                let key = input.call(Ident::parse_any)?;
+                let eq_token = input.parse::<Token![=]>()?;
+                metas.push(Meta::NameValue(MetaNameValue {
+                    path: key.into(),
+                    eq_token,
+                    value: input.parse::<Expr>()?,
+                }));
+            } else {
+                metas.push(input.parse::<Meta>()?);
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Comma>()?;
+        }
+        Ok(metas)
+    };
+    let metas = parse_metas.parse(attr)?;
+
+    for meta in metas {
+        // action_defaults(...) holds action options shared by all actions
+        if let Meta::List(list) = &meta {
+            if list.path.is_ident("action_defaults") {
+                let defaults = list.parse_args_with(Punctuated::<Meta, Comma>::parse_terminated)?;
+                for default in defaults {
+                    if default.path().is_ident("name")
+                        || default.path().is_ident("path")
+                        || default.path().is_ident("raw_payload")
+                    {
+                        return Err(syn::Error::new_spanned(
+                            default.path(),
+                            "Action name, path and raw_payload can't have service-wide defaults",
+                        ));
+                    }
+                    attrs.action_defaults.push(default);
+                }
+                continue;
+            }
+        }
+
+        let Meta::NameValue(name_value) = meta else {
+            return Err(syn::Error::new_spanned(
+                meta,
+                "Expected a `name = value` service attribute",
+            ));
+        };
+
+        let key = match name_value.path.get_ident() {
+            Some(ident) => ident.to_string(),
+            None => {
+                return Err(syn::Error::new_spanned(
+                    &name_value.path,
+                    "Expected a simple service attribute name",
+                ))
+            }
+        };
+
+        // The config is an expression evaluated at runtime, not a literal
+        if key == "config" {
+            attrs.config = Some(name_value.value);
+            continue;
+        }
+
+        // External topics are a list of topic literals
+        if key == "external_topics" {
+            let Expr::Array(topics) = &name_value.value else {
+                return Err(syn::Error::new_spanned(
+                    &name_value.value,
+                    "Expected a list of topics, e.g. external_topics = [\"billing/invoiced\"]",
+                ));
+            };
+            for topic in &topics.elems {
+                match topic {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit_str),
+                        ..
+                    }) => attrs.external_topics.push(lit_str.clone()),
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "Expected a string literal topic",
+                        ))
+                    }
+                }
+            }
+            continue;
+        }
+
+        // Flags only take a boolean literal, so a quoted "true" isn't silently accepted
+        if SERVICE_FLAG_OPTIONS.contains(&key.as_str()) {
+            let flag = expect_lit_bool(&name_value.value, &key)?;
+            attrs.values.insert(key, flag.value.to_string());
+            continue;
+        }
+
+        if !SERVICE_VALUE_OPTIONS.contains(&key.as_str()) {
+            return Err(syn::Error::new_spanned(
+                &name_value.path,
+                format!(
+                    "Unknown service attribute `{}`, expected one of: {}, {}, config, external_topics, action_defaults",
+                    key,
+                    SERVICE_VALUE_OPTIONS.join(", "),
+                    SERVICE_FLAG_OPTIONS.join(", ")
+                ),
+            ));
+        }
+
+        let value = match &name_value.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            }) => lit_str.value(),
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(lit_int),
+                ..
+            }) => lit_int.base10_digits().to_string(),
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    format!("Expected a literal value for service attribute `{}`", key),
+                ))
+            }
+        };
+        attrs.values.insert(key, value);
+    }
+
+    Ok(attrs)
+}
+
+/// Check whether an action sets one of its options itself
+type OptionIsSet = fn(&ActionImpl) -> bool;
+
+/// Options of `action_defaults`, with whether an action already sets the option itself, in
+/// which case the default isn't applied to it. `meta(...)` is merged per annotation key instead.
+const ACTION_DEFAULT_OPTIONS: &[(&str, OptionIsSet)] = &[
+    ("after", |action| action.after.is_some()),
+    ("enabled_if", |action| action.enabled_if.is_some()),
+    ("idempotency_key", |action| action.idempotency_key.is_some()),
+    ("idempotency_cache_size", |action| {
+        action.idempotency_cache_size.is_some()
+    }),
+    ("schema", |action| action.schema.is_some()),
+    ("raw_payload", |action| action.raw_payload),
+    ("max_concurrency", |action| action.max_concurrency.is_some()),
+    ("log_args", |action| action.log_args.is_some()),
+    ("params_struct", |action| action.params_struct.is_some()),
+    ("redact", |action| !action.redact.is_empty()),
+    ("outputs", |action| !action.outputs.is_empty()),
+    ("timeout_ms", |action| action.timeout_ms.is_some()),
+    ("on_timeout", |action| action.on_timeout.is_some()),
+    ("respect_deadline", |action| {
+        action.respect_deadline.is_some()
+    }),
+    ("paginate", |action| action.paginate.is_some()),
+    ("positional", |action| action.positional.is_some()),
+    ("serializer", |action| action.serializer.is_some()),
+    ("progress_topic", |action| action.progress_topic.is_some()),
+];
+
+/// Append the service-wide action defaults to each #[action] attribute
+///
+/// Options set on the action itself take precedence; for `meta(...)` the override
+/// is per annotation key. Unknown options are reported by the action macro.
+fn apply_action_defaults(input: &mut ItemImpl, defaults: &[Meta]) {
+    if defaults.is_empty() {
+        return;
+    }
+
+    for item in input.items.iter_mut() {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        let Some(attr) = method
+            .attrs
+            .iter_mut()
+            .find(|attr| is_macro_attribute(attr, "action"))
+        else {
+            continue;
+        };
+        // Attribute errors are reported by the action macro itself
+        let Ok(action_impl) = parse_action_attribute(attr) else {
+            continue;
+        };
+
+        let has_idempotency_key = action_impl.idempotency_key.is_some()
+            || defaults
+                .iter()
+                .any(|default| default.path().is_ident("idempotency_key"));
+
+        let mut added = Vec::new();
+        for default in defaults {
+            let path = default.path();
+            if path.is_ident("meta") {
+                let Meta::List(list) = default else {
+                    added.push(quote! { #default });
+                    continue;
+                };
+                let Ok(annotations) =
+                    list.parse_args_with(Punctuated::<MetaNameValue, Comma>::parse_terminated)
+                else {
+                    added.push(quote! { #default });
+                    continue;
+                };
+                let inherited = annotations
+                    .iter()
+                    .filter(|annotation| {
+                        !action_impl
+                            .annotations
+                            .iter()
+                            .any(|(key, _)| annotation.path.is_ident(key))
+                    })
+                    .collect::<Vec<_>>();
+                if !inherited.is_empty() {
+                    added.push(quote! { meta(#(#inherited),*) });
+                }
+                continue;
+            }
+
+            let overridden = ACTION_DEFAULT_OPTIONS
+                .iter()
+                .any(|(option, is_set)| path.is_ident(option) && is_set(&action_impl))
+                // The cache size only applies with a key, set on the action or defaulted
+                || (path.is_ident("idempotency_cache_size") && !has_idempotency_key);
+            if !overridden {
+                added.push(quote! { #default });
+            }
+        }
+
+        if added.is_empty() {
+            continue;
+        }
+
+        // Keep the attribute path as written, bare or fully qualified
+        let attr_path = attr.path().clone();
+        attr.meta = match &attr.meta {
+            Meta::List(list) if !list.tokens.is_empty() => {
+                let existing = &list.tokens;
+                parse_quote! { #attr_path(#existing, #(#added),*) }
+            }
+            _ => parse_quote! { #attr_path(#(#added),*) },
+        };
+    }
+}
+
+/// Rewrite the literal topics of the #[subscribe] and #[publish] attributes into the namespace
+///
+/// Relative topics become `namespace/topic`, absolute topics (leading `/`) are kept without the
+/// slash. Topics computed with `paths_from` are only known at runtime and are left as they are.
+fn apply_topic_namespace(input: &mut ItemImpl, namespace: &str) {
+    let namespace = namespace.trim_matches('/');
+
+    for item in input.items.iter_mut() {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        for attr in method.attrs.iter_mut().filter(|attr| {
+            is_macro_attribute(attr, "subscribe") || is_macro_attribute(attr, "publish")
+        }) {
+            let Meta::List(list) = &mut attr.meta else {
+                continue;
+            };
+
+            // The topic is either the leading string literal or the value of `path = "..."`
+            let mut tokens = list.tokens.clone().into_iter().collect::<Vec<_>>();
+            for index in 0..tokens.len() {
+                let is_topic = index == 0
+                    || (index >= 2
+                        && matches!(&tokens[index - 2], TokenTree::Ident(ident) if ident == "path")
+                        && matches!(&tokens[index - 1], TokenTree::Punct(punct) if punct.as_char() == '='));
+                if !is_topic {
+                    continue;
+                }
+                let TokenTree::Literal(literal) = &tokens[index] else {
+                    continue;
+                };
+                // Malformed attributes are reported by the subscribe and publish macros
+                let Ok(Lit::Str(topic)) =
+                    syn::parse2::<Lit>(TokenTree::Literal(literal.clone()).into())
+                else {
+                    continue;
+                };
+
+                let topic_value = topic.value();
+                let namespaced = match topic_value.strip_prefix('/') {
+                    Some(absolute) => absolute.to_string(),
+                    None => format!("{}/{}", namespace, topic_value),
+                };
+                let mut namespaced_literal = proc_macro2::Literal::string(&namespaced);
+                namespaced_literal.set_span(literal.span());
+                tokens[index] = TokenTree::Literal(namespaced_literal);
+            }
+            list.tokens = tokens.into_iter().collect();
+        }
+    }
+}
+
+/// Collect methods marked with #[action] or #[subscribe] in the impl block
+fn collect_action_methods(input: &ItemImpl) -> Vec<(Ident, &str, ImplItemFn)> {
+    // Find all methods marked with #[action] or #[subscribe]
+    let all_methods = input
+        .items
+        .iter()
+        .filter_map(|item| {
+            if let ImplItem::Fn(method) = item {
+                let is_action = method
+                    .attrs
+                    .iter()
+                    .any(|attr| is_macro_attribute(attr, "action"));
+                if is_action {
+                    Some((method.sig.ident.clone(), "action", method.clone()))
+                } else {
+                    let is_subscription = method
+                        .attrs
+                        .iter()
+                        .any(|attr| is_macro_attribute(attr, "subscribe"));
+                    if is_subscription {
+                        Some((method.sig.ident.clone(), "subscribe", method.clone()))
+                    } else {
+                        None
+                    }
+                }
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<(Ident, &str, ImplItemFn)>>();
+
+    all_methods
+}
+
+/// Find the #[action] attribute on a method
+fn find_action_attribute(method: &ImplItemFn) -> Option<&Attribute> {
+    method
+        .attrs
+        .iter()
+        .find(|attr| is_macro_attribute(attr, "action"))
+}
+
+/// Generate the service metadata static holder
+fn generate_service_metadata() -> TokenStream2 {
+    quote! {
+        // Static metadata holders
+        static SERVICE_NAME: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+        static SERVICE_PATH: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+        static SERVICE_DESCRIPTION: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+        static SERVICE_VERSION: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    }
+}
+
+/// Generate a hidden method bounded on `Self: Send + Sync`
+///
+/// Handlers capture the service in `Send` futures, so a field such as `Rc` otherwise fails deep
+/// inside the generated registration code. The bound fails on the service type itself instead.
+fn generate_send_sync_assertion(struct_type: &Ident) -> TokenStream2 {
+    quote_spanned! {struct_type.span()=>
+        impl #struct_type {
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            fn _assert_send_sync()
+            where
+                Self: Send + Sync,
+            {
+            }
+        }
+    }
+}
+
+/// Generate `<ACTION>_NAME` and `<ACTION>_PATH` constants for every action,
+/// `all_action_paths()` listing the path every action is registered under and
+/// `action_annotations()` mapping every action name to its annotations
+///
+/// The path constant is only generated when the service path is given inline,
+/// since it is otherwise only known at runtime. Two actions whose names map to
+/// the same constant are reported as an error.
+fn generate_action_constants(
+    struct_type: &Ident,
+    all_methods: &[(Ident, &str, ImplItemFn)],
+    service_attrs: &ServiceAttributes,
+) -> syn::Result<TokenStream2> {
+    let mut constant_names = HashSet::new();
+    let mut constants = Vec::new();
+    let mut action_paths = Vec::new();
+    let mut annotation_entries = Vec::new();
+
+    for (fn_ident, method_type, method) in all_methods {
+        if *method_type != "action" {
+            continue;
+        }
+        // Attribute errors are reported by the action macro itself
+        let Some(action_impl) =
+            find_action_attribute(method).and_then(|attr| parse_action_attribute(attr).ok())
+        else {
+            continue;
+        };
+
+        let action_name = action_impl
+            .name
+            .clone()
+            .unwrap_or_else(|| fn_ident.to_string());
+        let action_path = action_impl
+            .path
+            .clone()
+            .unwrap_or_else(|| action_name.clone());
+        action_paths.push(action_path.clone());
+
+        let annotations_method_name = format_ident!("action_annotations_{}", fn_ident);
+        annotation_entries.push(quote! {
+            (#action_name.to_string(), Self::#annotations_method_name())
+        });
+
+        let prefix: String = action_name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if !constant_names.insert(prefix.clone()) {
+            return Err(syn::Error::new_spanned(
+                &method.sig.ident,
+                format!(
+                    "Action '{}' maps to the constant {}_NAME already generated for another action",
+                    action_name, prefix
+                ),
+            ));
+        }
+
+        let name_const = format_ident!("{}_NAME", prefix);
+        let name_doc = format!("Name of the `{}` action", action_name);
+        constants.push(quote! {
+            #[doc = #name_doc]
+            pub const #name_const: &'static str = #action_name;
+        });
+
+        if let Some(service_path) = service_attrs.get("path") {
+            let path_const = format_ident!("{}_PATH", prefix);
+            let full_path = format!("{}/{}", service_path.trim_end_matches('/'), action_path);
+            let path_doc = format!("Full path of the `{}` action", action_name);
+            constants.push(quote! {
+                #[doc = #path_doc]
+                pub const #path_const: &'static str = #full_path;
+            });
+        }
+    }
+
+    Ok(quote! {
+        impl #struct_type {
+            #(#constants)*
+
+            /// Paths of all actions declared by this service, relative to the service path
+            ///
+            /// Includes actions with an `enabled_if` predicate, whether or not they get registered.
+            pub fn all_action_paths() -> &'static [&'static str] {
+                &[#(#action_paths),*]
+            }
+
+            /// Annotations of every action, keyed by action name
+            ///
+            /// Holds the `#[action(meta(...))]` entries merged with the service's
+            /// `action_defaults`, plus `log_args = "false"` for actions that don't log arguments.
+            pub fn action_annotations(
+            ) -> std::collections::HashMap<String, std::collections::HashMap<String, String>> {
+                std::collections::HashMap::from([#(#annotation_entries),*])
+            }
+        }
+    })
+}
+
+/// Generate an `<Action>Params` struct with a `to_value()` payload builder for every action
+/// with `params_struct = true`
+///
+/// Fields follow the action's payload parameters, string views become owned `String`s. The
+/// payload is built the way the action reads it: a single parameter is the payload itself,
+/// several are the entries of a map, and positional parameters are the elements of a list.
+fn generate_params_structs(all_methods: &[(Ident, &str, ImplItemFn)]) -> TokenStream2 {
+    let mut params_structs = Vec::new();
+
+    for (fn_ident, method_type, method) in all_methods {
+        if *method_type != "action" {
+            continue;
+        }
+        // Attribute errors are reported by the action macro itself
+        let Some(action_impl) =
+            find_action_attribute(method).and_then(|attr| parse_action_attribute(attr).ok())
+        else {
+            continue;
+        };
+        if !action_impl
+            .params_struct
+            .as_ref()
+            .is_some_and(|params_struct| params_struct.value)
+        {
+            continue;
+        }
+
+        // Same parameter rules as the action macro, values read from the context aren't payload
+        let params = method
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(PatType { pat, ty, attrs, .. }) => match &**pat {
+                    Pat::Ident(pat_ident) => {
+                        let ident_string = pat_ident.ident.to_string();
+                        let is_context = !has_marker(attrs, "param")
+                            && (ident_string == "ctx" || ident_string.ends_with("ctx"));
+                        if is_context
+                            || has_marker(attrs, "request_id")
+                            || has_marker(attrs, "cancel")
+                            || has_marker(attrs, "file")
+                        {
+                            None
+                        } else {
+                            Some((pat_ident.ident.clone(), (**ty).clone()))
+                        }
+                    }
+                    _ => None,
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        let field_idents = params.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
+        let field_types = params.iter().map(|(_, ty)| {
+            let type_str = quote! { #ty }.to_string();
+            if type_str == "& str" || is_cow_str_type(&type_str) {
+                quote! { String }
+            } else {
+                quote! { #ty }
+            }
+        });
+        let values = params
+            .iter()
+            .map(|(ident, ty)| {
+                let type_str = quote! { #ty }.to_string();
+                if type_str.ends_with("ArcValueType") {
+                    quote! { #ident }
+                } else if type_str == "& str"
+                    || is_cow_str_type(&type_str)
+                    || is_primitive_type(&type_str)
+                {
+                    quote! { runar_common::types::ArcValueType::new_primitive(#ident) }
+                } else {
+                    quote! { runar_common::types::ArcValueType::from_struct(#ident) }
+                }
+            })
+            .collect::<Vec<_>>();
+        let payload = match values.as_slice() {
+            _ if action_impl.is_positional() => quote! {
+                runar_common::types::ArcValueType::new_list(vec![#(#values),*])
+            },
+            [value] => quote! { #value },
+            _ => {
+                let param_names = params.iter().map(|(ident, _)| ident.to_string());
+                quote! {
+                    let mut map = std::collections::HashMap::<String, runar_common::types::ArcValueType>::new();
+                    #(map.insert(#param_names.to_string(), #values);)*
+                    runar_common::types::ArcValueType::new_map(map)
+                }
+            }
+        };
+
+        let action_name = action_impl
+            .name
+            .clone()
+            .unwrap_or_else(|| fn_ident.to_string());
+        let params_type = format_ident!("{}Params", to_pascal_case(&fn_ident.to_string()));
+        let struct_doc = format!("Typed parameters of the `{}` action", action_name);
+        let to_value_doc = format!("Build the request payload of the `{}` action", action_name);
+        params_structs.push(quote! {
+            #[doc = #struct_doc]
+            #[derive(Clone)]
+            pub struct #params_type {
+                #(pub #field_idents: #field_types,)*
+            }
+
+            impl #params_type {
+                #[doc = #to_value_doc]
+                pub fn to_value(&self) -> runar_common::types::ArcValueType {
+                    let Self { #(#field_idents),* } = self.clone();
+                    #payload
+                }
+            }
+        });
+    }
+
+    quote! { #(#params_structs)* }
+}
+
+/// Convert a snake_case name such as `get_user` into PascalCase (`GetUser`)
+fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Generate a `<Service>Events` helper with one typed method per distinct #[publish] topic
+///
+/// The payload type of each method is the `Ok` type of the publishing action.
+fn generate_events_emitter(struct_type: &Ident, input: &ItemImpl) -> TokenStream2 {
+    let mut topics = HashSet::new();
+    let mut publish_methods = Vec::new();
+
+    for item in &input.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        let Some(payload_type) = extract_result_ok_type(&method.sig.output) else {
+            continue;
+        };
+
+        // Malformed attributes are reported by the publish macro itself
+        let publish_impls = method
+            .attrs
+            .iter()
+            .filter(|attr| is_macro_attribute(attr, "publish"))
+            .filter_map(|attr| attr.parse_args::<PublishImpl>().ok());
+
+        // Topics computed with paths_from aren't known until the action runs
+        for publish_impl in publish_impls {
+            let Some(path) = publish_impl.path else {
+                continue;
+            };
+            let topic = path.value();
+            if !topics.insert(topic.clone()) {
+                continue;
+            }
+
+            let method_name = format_ident!("publish_{}", topic_to_ident(&topic));
+            let doc = format!("Publish a `{}` event", topic);
+            // A published value expression isn't typed by the action's result
+            let payload_type = match publish_impl.value {
+                Some(_) => quote! { impl serde::Serialize + Send + Sync + 'static },
+                None => quote! { #payload_type },
+            };
+            publish_methods.push(quote! {
+                #[doc = #doc]
+                pub async fn #method_name(&self, payload: #payload_type) -> anyhow::Result<()> {
+                    self.ctx
+                        .publish(#topic, Some(runar_common::types::ArcValueType::from_struct(payload)))
+                        .await
+                }
+            });
+        }
+    }
+
+    if publish_methods.is_empty() {
+        return TokenStream2::new();
+    }
+
+    let events_type = format_ident!("{}Events", struct_type);
+    let struct_doc = format!(
+        "Typed event emitter for the topics published by {}",
+        struct_type
+    );
+
+    quote! {
+        #[doc = #struct_doc]
+        pub struct #events_type<'a> {
+            ctx: &'a runar_node::services::RequestContext,
+        }
+
+        impl<'a> #events_type<'a> {
+            /// Create an event emitter publishing through the given request context
+            pub fn new(ctx: &'a runar_node::services::RequestContext) -> Self {
+                Self { ctx }
+            }
+
+            #(#publish_methods)*
+        }
+
+        impl #struct_type {
+            /// Get the typed event emitter for this service's published topics
+            pub fn events(ctx: &runar_node::services::RequestContext) -> #events_type<'_> {
+                #events_type::new(ctx)
+            }
+        }
+    }
+}
+
+/// Generate a warning for every subscription no topic of the service or `external_topics`
+/// matches, and for every published topic no subscription of the service listens on
+///
+/// Topics without a `/` are resolved against the service path when it is given inline. Stable
+/// proc macros can't emit warnings, so each one is the use of a `#[deprecated]` constant
+/// spanned to the topic literal.
+fn generate_topic_check(input: &ItemImpl, service_attrs: &ServiceAttributes) -> TokenStream2 {
+    let resolve = |topic: &str| match service_attrs.get("path") {
+        Some(service_path) if !topic.contains('/') => {
+            format!("{}/{}", service_path.trim_matches('/'), topic)
+        }
+        _ => topic.to_string(),
+    };
+
+    let mut subscriptions = Vec::new();
+    let mut published = Vec::new();
+    let mut has_dynamic_topics = false;
+    for item in &input.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        // Malformed attributes are reported by the macros themselves
+        for attr in &method.attrs {
+            if is_macro_attribute(attr, "subscribe") {
+                if let Ok(subscribe_impl) = attr.parse_args::<SubscribeImpl>() {
+                    subscriptions.push(subscribe_impl.path);
+                }
+            } else if is_macro_attribute(attr, "publish") {
+                if let Ok(publish_impl) = attr.parse_args::<PublishImpl>() {
+                    match publish_impl.path {
+                        Some(path) => published.push(path),
+                        None => has_dynamic_topics = true,
+                    }
+                }
+            } else if is_macro_attribute(attr, "action") {
+                if let Ok(ActionImpl {
+                    progress_topic: Some(progress_topic),
+                    ..
+                }) = parse_action_attribute(attr)
+                {
+                    published.push(progress_topic);
+                }
+            }
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let mut warn = |topic: &LitStr, message: String| {
+        let warning_ident =
+            format_ident!("unmatched_topic_{}", warnings.len(), span = topic.span());
+        warnings.push(quote_spanned! {topic.span()=>
+            const _: () = {
+                #[deprecated(note = #message)]
+                #[allow(non_upper_case_globals)]
+                const #warning_ident: () = ();
+                #warning_ident
+            };
+        });
+    };
+
+    // Topics computed with paths_from are only known at runtime and could match anything
+    if !has_dynamic_topics {
+        for subscription in &subscriptions {
+            let pattern = resolve(&subscription.value());
+            let matched = published
+                .iter()
+                .chain(&service_attrs.external_topics)
+                .any(|topic| topic_matches(&pattern, &resolve(&topic.value())));
+            if !matched {
+                warn(
+                    subscription,
+                    format!(
+                        "check_topics: the service subscribes to `{}`, which it never publishes and which isn't listed in external_topics",
+                        pattern
+                    ),
+                );
+            }
+        }
+    }
+    for topic in &published {
+        let topic_value = resolve(&topic.value());
+        let matched = subscriptions
+            .iter()
+            .any(|subscription| topic_matches(&resolve(&subscription.value()), &topic_value));
+        if !matched {
+            warn(
+                topic,
+                format!(
+                    "check_topics: the service publishes `{}`, which none of its subscriptions listens on",
+                    topic_value
+                ),
+            );
+        }
+    }
+
+    quote! { #(#warnings)* }
+}
+
+/// Check whether a subscription pattern matches a topic
+///
+/// `*` and `{name}` captures match one segment, a trailing `>` matches the rest. A `{name}`
+/// segment of the topic, as in progress topics, matches any segment.
+fn topic_matches(pattern: &str, topic: &str) -> bool {
+    let is_capture = |segment: &str| segment.starts_with('{') && segment.ends_with('}');
+    let mut pattern_segments = pattern.trim_matches('/').split('/');
+    let mut topic_segments = topic.trim_matches('/').split('/');
+    loop {
+        match (pattern_segments.next(), topic_segments.next()) {
+            (Some(">"), Some(_)) => return true,
+            (Some(pattern_segment), Some(topic_segment)) => {
+                let segment_matches = pattern_segment == "*"
+                    || is_capture(pattern_segment)
+                    || is_capture(topic_segment)
+                    || pattern_segment == topic_segment;
+                if !segment_matches {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Convert a topic such as `math/my_data_auto` into an identifier fragment
+fn topic_to_ident(topic: &str) -> String {
+    topic
+        .trim_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
 
-    // Extract the struct name
-    let struct_type = match &*input.self_ty {
-        Type::Path(TypePath { ref path, .. }) => path.segments.last().unwrap().ident.clone(),
-        _ => panic!("Service macro can only be applied to structs"),
-    };
+/// Remove the `#[on_warmup]` markers, returning each marked method's name and marker
+fn take_warmup_hooks(input: &mut ItemImpl) -> Vec<(Ident, Attribute)> {
+    let mut hooks = Vec::new();
+    for item in input.items.iter_mut() {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        if let Some(index) = method
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("on_warmup"))
+        {
+            let marker = method.attrs.remove(index);
+            hooks.push((method.sig.ident.clone(), marker));
+        }
+    }
+    hooks
+}
 
-    // Extract the service attributes from the macro annotation
-    let service_attrs = extract_service_attributes(attr);
+/// Generate the `warmup` method enabled with `#[service(warmup = true)]`
+///
+/// The hooks run in declaration order, the first failing hook stops the warmup.
+fn generate_warmup_method(
+    struct_type: &Ident,
+    warmup_hooks: &[(Ident, Attribute)],
+) -> TokenStream2 {
+    // Called with the span of the hook, so a signature mismatch is reported on the method
+    let hook_calls = warmup_hooks.iter().map(|(hook, _)| {
+        quote_spanned! {hook.span()=>
+            context.debug(format!("Running warmup hook {}", stringify!(#hook)));
+            self.#hook(context).await?;
+        }
+    });
 
-    // Find all methods marked with #[action] or #[subscribe]
-    let all_methods = collect_action_methods(&input);
+    quote! {
+        impl #struct_type {
+            /// Run the `#[on_warmup]` hooks to pre-populate caches or open connections
+            ///
+            /// Called by `start` once the service is initialized, and may be called again to re-warm.
+            pub async fn warmup(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                context.info(format!("Warming up service {}", stringify!(#struct_type)));
+                #(#hook_calls)*
+                Ok(())
+            }
+        }
+    }
+}
 
-    // Generate the service metadata
-    let service_metadata = generate_service_metadata();
+/// Generate the built-in `health` action enabled with `#[service(health = true)]`
+///
+/// The action returns a map with the service name, version, uptime in seconds and state.
+/// Uptime is measured from the instance's `init()`, when the handler is registered and
+/// captures the time, so each instance reports its own uptime.
+fn generate_health_action(struct_type: &Ident) -> TokenStream2 {
+    quote! {
+        impl #struct_type {
+            #[doc(hidden)]
+            async fn register_health_action(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()>
+            where
+                for<'__service> Self: Send + Sync,
+            {
+                context.logger.info("Registering 'health' action".to_string());
 
-    // Generate the trait implementation for the AbstractService trait
-    let service_impl = generate_abstract_service_impl(&struct_type, &all_methods, &service_attrs);
+                // Shared by every request instead of cloning the service per request
+                let service = std::sync::Arc::new(self.clone());
+                let registered_at = std::time::Instant::now();
 
-    // Return the input struct unchanged along with the trait implementation
-    TokenStream::from(quote! {
-        #input
+                let handler = std::sync::Arc::new(move |_params_opt: Option<runar_common::types::ArcValueType>, _ctx: runar_node::services::RequestContext|
+                    -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Option<runar_common::types::ArcValueType>, anyhow::Error>> + Send>> {
+                    let service = service.clone();
 
-        #service_metadata
+                    Box::pin(async move {
+                        let uptime_secs = registered_at.elapsed().as_secs();
 
-        #service_impl
-    })
+                        let mut health = std::collections::HashMap::<String, runar_common::types::ArcValueType>::new();
+                        health.insert(
+                            "name".to_string(),
+                            runar_common::types::ArcValueType::new_primitive(
+                                runar_node::services::abstract_service::AbstractService::name(&*service).to_string(),
+                            ),
+                        );
+                        health.insert(
+                            "version".to_string(),
+                            runar_common::types::ArcValueType::new_primitive(
+                                runar_node::services::abstract_service::AbstractService::version(&*service).to_string(),
+                            ),
+                        );
+                        health.insert(
+                            "uptime_secs".to_string(),
+                            runar_common::types::ArcValueType::new_primitive(uptime_secs),
+                        );
+                        health.insert(
+                            "state".to_string(),
+                            runar_common::types::ArcValueType::new_primitive("Running".to_string()),
+                        );
+
+                        Ok(Some(runar_common::types::ArcValueType::new_map(health)))
+                    })
+                });
+
+                context.register_action("health".to_string(), handler).await
+            }
+        }
+    }
 }
 
-/// Extract service attributes from the TokenStream
-fn extract_service_attributes(attr: TokenStream) -> HashMap<String, String> {
-    let mut attrs = HashMap::new();
+/// Actions of the service in declaration order, with their parsed attribute and path
+fn dispatched_actions<'a>(
+    all_methods: &'a [(Ident, &str, ImplItemFn)],
+) -> Vec<(&'a Ident, &'a ImplItemFn, ActionImpl, String)> {
+    all_methods
+        .iter()
+        .filter(|(_, method_type, _)| *method_type == "action")
+        .filter_map(|(fn_ident, _, method)| {
+            // Attribute errors are reported by the action macro itself
+            let action_impl =
+                find_action_attribute(method).and_then(|attr| parse_action_attribute(attr).ok())?;
+            let action_name = action_impl
+                .name
+                .clone()
+                .unwrap_or_else(|| fn_ident.to_string());
+            let action_path = action_impl.path.clone().unwrap_or(action_name);
+            Some((fn_ident, method, action_impl, action_path))
+        })
+        .collect()
+}
 
-    if attr.is_empty() {
-        return attrs;
+/// Generate `dispatcher`, enabled with `#[service(static_dispatch = true)]` or
+/// `#[service(jsonrpc = true)]`
+///
+/// The dispatcher owns the action handlers of the instance it was built from and
+/// looks one up with a `match` over the action paths.
+fn generate_dispatcher(
+    struct_type: &Ident,
+    all_methods: &[(Ident, &str, ImplItemFn)],
+) -> TokenStream2 {
+    let dispatcher_ident = format_ident!("{}Dispatcher", struct_type);
+    let mut path_arms = Vec::new();
+    let mut handler_builders = Vec::new();
+
+    for (index, (fn_ident, _, action_impl, action_path)) in
+        dispatched_actions(all_methods).into_iter().enumerate()
+    {
+        // Like registration, disabled actions are left out when the table is built
+        let handler_method_name = format_ident!("action_handler_{}", fn_ident);
+        handler_builders.push(match &action_impl.enabled_if {
+            Some(predicate) => quote! {
+                if #predicate { Some(self.#handler_method_name()?) } else { None }
+            },
+            None => quote! { Some(self.#handler_method_name()?) },
+        });
+        path_arms.push(quote! { #action_path => #index, });
     }
 
-    // Convert attribute tokens to a string for simple parsing
-    let attr_str = attr.to_string();
+    let dispatcher_doc = format!(
+        "Action handlers of a `{}` instance, built with `{}::dispatcher`",
+        struct_type, struct_type
+    );
+
+    quote! {
+        #[doc = #dispatcher_doc]
+        pub struct #dispatcher_ident {
+            service_path: String,
+            handlers: Vec<Option<runar_node::services::ActionHandler>>,
+        }
 
-    // Simple parsing of name = "value" pairs
-    for pair in attr_str.split(',') {
-        let parts: Vec<&str> = pair.split('=').collect();
-        if parts.len() == 2 {
-            let key = parts[0].trim().to_string();
+        impl #struct_type {
+            /// Build the dispatch table of this instance
+            ///
+            /// The handlers share the per-action state (idempotency cache, concurrency
+            /// limit) of the dispatcher, so build it once and keep it around.
+            pub fn dispatcher(&self) -> anyhow::Result<#dispatcher_ident>
+            where
+                for<'__service> Self: Send + Sync,
+            {
+                Ok(#dispatcher_ident {
+                    service_path: runar_node::services::abstract_service::AbstractService::path(self)
+                        .to_string(),
+                    handlers: vec![#(#handler_builders),*],
+                })
+            }
+        }
 
-            // Extract the string value between quotes
-            let value_part = parts[1].trim();
-            if value_part.starts_with('"') && value_part.ends_with('"') {
-                let value = value_part[1..value_part.len() - 1].to_string();
-                attrs.insert(key, value);
+        impl #dispatcher_ident {
+            /// Handler of the action at `action_path`, relative to the service
+            #[allow(dead_code)]
+            fn handler(&self, action_path: &str) -> Option<&runar_node::services::ActionHandler> {
+                let index: usize = match action_path {
+                    #(#path_arms)*
+                    _ => return None,
+                };
+                self.handlers[index].as_ref()
             }
         }
     }
+}
+
+/// Generate `dispatch` on the dispatcher, enabled with `#[service(static_dispatch = true)]`
+fn generate_static_dispatch(struct_type: &Ident) -> TokenStream2 {
+    let dispatcher_ident = format_ident!("{}Dispatcher", struct_type);
 
-    attrs
+    quote! {
+        impl #dispatcher_ident {
+            /// Call the action at `path`, relative to the service or prefixed with its path
+            pub async fn dispatch(
+                &self,
+                path: &str,
+                params: Option<runar_common::types::ArcValueType>,
+                ctx: runar_node::services::RequestContext,
+            ) -> anyhow::Result<Option<runar_common::types::ArcValueType>> {
+                let action_path = path
+                    .strip_prefix(self.service_path.as_str())
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .unwrap_or(path);
+
+                match self.handler(action_path) {
+                    Some(handler) => handler(params, ctx).await,
+                    None => Err(anyhow::anyhow!(format!("No action found for path {}", path))),
+                }
+            }
+        }
+    }
 }
 
-/// Collect methods marked with #[action] or #[subscribe] in the impl block
-fn collect_action_methods(input: &ItemImpl) -> Vec<(Ident, &str, ImplItemFn)> {
-    // Find all methods marked with #[action] or #[subscribe]
-    let all_methods = input
-        .items
-        .iter()
-        .filter_map(|item| {
-            if let ImplItem::Fn(method) = item {
-                let is_action = method
-                    .attrs
-                    .iter()
-                    .any(|attr| attr.path().is_ident("action"));
-                if is_action {
-                    Some((method.sig.ident.clone(), "action", method.clone()))
+/// Generate `handle_jsonrpc` on the service and its dispatcher, enabled with
+/// `#[service(jsonrpc = true)]`
+///
+/// JSON-RPC method names are the action paths. The params, by name (object) or by
+/// position (array), are converted to the payload the action handler reads, and the
+/// request goes through the same handler as requests routed by the node.
+fn generate_jsonrpc_dispatcher(
+    struct_type: &Ident,
+    all_methods: &[(Ident, &str, ImplItemFn)],
+) -> TokenStream2 {
+    let dispatcher_ident = format_ident!("{}Dispatcher", struct_type);
+    let mut payload_arms = Vec::new();
+
+    for (_, method, action_impl, action_path) in dispatched_actions(all_methods) {
+        // Same parameter rules as the action macro: everything but self, the context and
+        // the #[request_id], #[cancel] and #[file] parameters read from the context
+        let params: Vec<(Ident, Type, Vec<LitStr>)> = method
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(PatType { pat, ty, attrs, .. }) => match &**pat {
+                    Pat::Ident(pat_ident) => {
+                        let ident_string = pat_ident.ident.to_string();
+                        let is_context = !has_marker(attrs, "param")
+                            && (ident_string == "ctx" || ident_string.ends_with("ctx"));
+                        if is_context
+                            || has_marker(attrs, "request_id")
+                            || has_marker(attrs, "cancel")
+                            || has_marker(attrs, "file")
+                        {
+                            None
+                        } else {
+                            // Alias errors are reported by the action macro
+                            let aliases = parameter_aliases(attrs).unwrap_or_default();
+                            Some((pat_ident.ident.clone(), (**ty).clone(), aliases))
+                        }
+                    }
+                    _ => None,
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+
+        // With log_args = false the error, which can quote the value, is left out
+        let (err_pat, err_detail) = action_impl.error_tokens();
+
+        // Primitives are decoded to the parameter type so the handler finds the exact type
+        // it reads, anything else is handed over as a struct
+        let convert = |name: &str, ty: &Type| {
+            let type_str = quote! { #ty }.to_string();
+            if is_primitive_param_type(&type_str) {
+                let value_type = if is_cow_str_type(&type_str) || type_str == "& str" {
+                    quote! { String }
                 } else {
-                    let is_subscription = method
-                        .attrs
-                        .iter()
-                        .any(|attr| attr.path().is_ident("subscribe"));
-                    if is_subscription {
-                        Some((method.sig.ident.clone(), "subscribe", method.clone()))
-                    } else {
-                        None
+                    quote! { #ty }
+                };
+                quote! {
+                    match serde_json::from_value::<#value_type>(value) {
+                        Ok(val) => runar_common::types::ArcValueType::new_primitive(val),
+                        Err(#err_pat) => break 'call Err(error_response(-32602, format!("Invalid params: {}: {}", #name, #err_detail))),
                     }
                 }
             } else {
-                None
+                quote! { runar_common::types::ArcValueType::from_struct(value) }
             }
-        })
-        .collect::<Vec<(Ident, &str, ImplItemFn)>>();
+        };
 
-    all_methods
+        let payload = if action_impl.raw_payload {
+            quote! {
+                match params {
+                    serde_json::Value::Null => None,
+                    params => Some(runar_common::types::ArcValueType::from_struct(params)),
+                }
+            }
+        } else if action_impl.is_positional() && !params.is_empty() {
+            // Positional actions read a list, named params are put in declaration order
+            let names = params.iter().map(|(ident, _, _)| ident.to_string());
+            let indexes = 0..params.len();
+            let conversions = params
+                .iter()
+                .map(|(ident, ty, _)| convert(&ident.to_string(), ty));
+            quote! {
+                let list = match params {
+                    serde_json::Value::Array(list) => list,
+                    serde_json::Value::Object(map) => {
+                        [#(#names),*].iter().map_while(|name| map.get(*name).cloned()).collect()
+                    }
+                    serde_json::Value::Null => Vec::new(),
+                    _ => break 'call Err(error_response(-32602, "Invalid params: expected an object or an array".to_string())),
+                };
+                let mut values = Vec::<runar_common::types::ArcValueType>::new();
+                'list: {
+                    #(
+                        let Some(value) = list.get(#indexes).cloned() else {
+                            break 'list;
+                        };
+                        values.push(#conversions);
+                    )*
+                }
+                Some(runar_common::types::ArcValueType::new_list(values))
+            }
+        } else if params.len() == 1 && !action_impl.paginates() {
+            // A single parameter is the whole payload
+            let (ident, ty, aliases) = &params[0];
+            let name = ident.to_string();
+            let conversion = convert(&name, ty);
+            quote! {
+                let value = match &params {
+                    serde_json::Value::Object(map) => map.get(#name)#(.or_else(|| map.get(#aliases)))*.cloned(),
+                    serde_json::Value::Array(list) => list.first().cloned(),
+                    serde_json::Value::Null => None,
+                    _ => break 'call Err(error_response(-32602, "Invalid params: expected an object or an array".to_string())),
+                };
+                match value {
+                    Some(value) => Some(#conversion),
+                    None => None,
+                }
+            }
+        } else {
+            // Everything else reads a map keyed by parameter name, positional params are
+            // keyed in declaration order
+            let names = params.iter().map(|(ident, _, _)| ident.to_string());
+            let array_arm = if params.is_empty() {
+                quote! { serde_json::Value::Array(_) => serde_json::Map::new(), }
+            } else {
+                quote! {
+                    serde_json::Value::Array(list) => {
+                        [#(#names),*].iter().map(|name| name.to_string()).zip(list).collect()
+                    }
+                }
+            };
+            let key_arms = params.iter().map(|(ident, ty, aliases)| {
+                let name = ident.to_string();
+                let conversion = convert(&name, ty);
+                quote! { #name #(| #aliases)* => #conversion, }
+            });
+            let page_arms = if action_impl.paginates() {
+                let usize_type: Type = syn::parse_quote! { usize };
+                let cursor = convert("cursor", &usize_type);
+                let limit = convert("limit", &usize_type);
+                quote! {
+                    "cursor" => #cursor,
+                    "limit" => #limit,
+                }
+            } else {
+                TokenStream2::new()
+            };
+            quote! {
+                let map = match params {
+                    serde_json::Value::Object(map) => map,
+                    #array_arm
+                    serde_json::Value::Null => break 'call Ok(None),
+                    _ => break 'call Err(error_response(-32602, "Invalid params: expected an object or an array".to_string())),
+                };
+                let mut values = std::collections::HashMap::<String, runar_common::types::ArcValueType>::new();
+                for (key, value) in map {
+                    let value = match key.as_str() {
+                        #(#key_arms)*
+                        #page_arms
+                        _ => runar_common::types::ArcValueType::from_struct(value),
+                    };
+                    values.insert(key, value);
+                }
+                Some(runar_common::types::ArcValueType::new_map(values))
+            }
+        };
+
+        payload_arms.push(quote! {
+            #action_path => 'call: {
+                Ok({ #payload })
+            }
+        });
+    }
+
+    quote! {
+        impl #struct_type {
+            /// Handle a JSON-RPC 2.0 request by calling the action whose path matches the method
+            ///
+            /// Returns `serde_json::Value::Null` for notifications (requests without an `id`).
+            /// The request runs on a dispatch table built for it, so per-action state such as
+            /// idempotency caches and concurrency limits only carries over between requests
+            /// handled by the same [`dispatcher`](Self::dispatcher).
+            pub async fn handle_jsonrpc(
+                &self,
+                req: serde_json::Value,
+                ctx: &runar_node::services::RequestContext,
+            ) -> serde_json::Value {
+                match self.dispatcher() {
+                    Ok(dispatcher) => dispatcher.handle_jsonrpc(req, ctx).await,
+                    Err(err) => match req.get("id") {
+                        // Notifications don't get a response
+                        None => serde_json::Value::Null,
+                        Some(id) => serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "error": { "code": -32603, "message": err.to_string() },
+                            "id": id,
+                        }),
+                    },
+                }
+            }
+        }
+
+        impl #dispatcher_ident {
+            /// Handle a JSON-RPC 2.0 request by calling the action whose path matches the method
+            ///
+            /// Returns `serde_json::Value::Null` for notifications (requests without an `id`).
+            pub async fn handle_jsonrpc(
+                &self,
+                req: serde_json::Value,
+                ctx: &runar_node::services::RequestContext,
+            ) -> serde_json::Value {
+                let id = req.get("id").cloned();
+                let error_response = |code: i64, message: String| {
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "error": { "code": code, "message": message },
+                        "id": id.clone().unwrap_or(serde_json::Value::Null),
+                    })
+                };
+
+                let method = match (
+                    req.get("jsonrpc").and_then(|version| version.as_str()),
+                    req.get("method").and_then(|method| method.as_str()),
+                ) {
+                    (Some("2.0"), Some(method)) => method,
+                    _ => return error_response(-32600, "Invalid Request".to_string()),
+                };
+                let params = req.get("params").cloned().unwrap_or(serde_json::Value::Null);
+
+                let response: Result<serde_json::Value, serde_json::Value> = 'response: {
+                    let Some(handler) = self.handler(method) else {
+                        break 'response Err(error_response(-32601, format!("Method not found: {}", method)));
+                    };
+
+                    // Convert the params to the payload the handler reads
+                    let payload: Result<Option<runar_common::types::ArcValueType>, serde_json::Value> =
+                        match method {
+                            #(#payload_arms)*
+                            _ => Err(error_response(-32601, format!("Method not found: {}", method))),
+                        };
+                    let payload = match payload {
+                        Ok(payload) => payload,
+                        Err(error) => break 'response Err(error),
+                    };
+
+                    match handler(payload, ctx.clone()).await {
+                        Ok(Some(mut result)) => result
+                            .as_type::<serde_json::Value>()
+                            .map_err(|err| error_response(-32603, err.to_string())),
+                        Ok(None) => Ok(serde_json::Value::Null),
+                        Err(err) => Err(error_response(-32603, err.to_string())),
+                    }
+                };
+
+                // Notifications don't get a response
+                if id.is_none() {
+                    return serde_json::Value::Null;
+                }
+
+                match response {
+                    Ok(result) => serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "result": result,
+                        "id": id,
+                    }),
+                    Err(error) => error,
+                }
+            }
+        }
+    }
 }
 
-/// Generate the service metadata static holder
-fn generate_service_metadata() -> TokenStream2 {
+/// Generate the action trait for `trait = "Name"` (trait and impl) or `impl_trait = "path::Name"`
+/// (impl of a trait generated for another service)
+///
+/// The trait has one async method per action with the action's signature, and the impl
+/// forwards to the service's inherent method.
+fn generate_action_trait(
+    struct_type: &Ident,
+    all_methods: &[(Ident, &str, ImplItemFn)],
+    service_attrs: &ServiceAttributes,
+) -> syn::Result<TokenStream2> {
+    let (trait_path, define) = match (service_attrs.get("trait"), service_attrs.get("impl_trait")) {
+        (None, None) => return Ok(TokenStream2::new()),
+        (Some(_), Some(_)) => {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "Use either trait (define and implement a trait) or impl_trait (implement an existing one), not both",
+            ))
+        }
+        (Some(trait_name), None) => (trait_name, true),
+        (None, Some(trait_path)) => (trait_path, false),
+    };
+    let trait_path = syn::parse_str::<syn::Path>(trait_path).map_err(|_| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("Expected a trait name, found `{}`", trait_path),
+        )
+    })?;
+
+    let mut declarations = Vec::new();
+    let mut forwards = Vec::new();
+    for (fn_ident, method_type, method) in all_methods {
+        if *method_type != "action" {
+            continue;
+        }
+
+        // Same signature without the parameter markers, patterns become plain names
+        let mut sig = method.sig.clone();
+        strip_markers(&mut sig, PARAMETER_MARKERS);
+        let mut args = Vec::new();
+        for (index, arg) in sig.inputs.iter_mut().enumerate() {
+            if let FnArg::Typed(pat_type) = arg {
+                let ident = match &*pat_type.pat {
+                    Pat::Ident(pat_ident) => pat_ident.ident.clone(),
+                    _ => format_ident!("arg{}", index),
+                };
+                *pat_type.pat = parse_quote! { #ident };
+                args.push(ident);
+            }
+        }
+
+        let docs = method
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+        let call = if sig.asyncness.is_some() {
+            quote! { #struct_type::#fn_ident(self, #(#args),*).await }
+        } else {
+            quote! { #struct_type::#fn_ident(self, #(#args),*) }
+        };
+        // The declaration copies the action's signature, however many parameters it has
+        declarations.push(quote! {
+            #(#docs)*
+            #[allow(clippy::too_many_arguments)]
+            #sig;
+        });
+        forwards.push(quote! {
+            #sig {
+                #call
+            }
+        });
+    }
+
+    let trait_definition = if define {
+        let Some(trait_ident) = trait_path.get_ident() else {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "trait expects a plain trait name, use impl_trait to implement a trait from another module",
+            ));
+        };
+        let trait_doc = format!(
+            "Actions of [`{}`], implemented by every service declaring `impl_trait = \"{}\"`",
+            struct_type, trait_ident
+        );
+        quote! {
+            #[doc = #trait_doc]
+            #[async_trait::async_trait]
+            pub trait #trait_ident: Send + Sync {
+                #(#declarations)*
+            }
+        }
+    } else {
+        TokenStream2::new()
+    };
+
+    Ok(quote! {
+        #trait_definition
+
+        #[async_trait::async_trait]
+        impl #trait_path for #struct_type {
+            #(#forwards)*
+        }
+    })
+}
+
+/// Collect the non-primitive types used by the service's actions and subscriptions,
+/// sorted for consistent output
+///
+/// Returns the types received in payloads, which the serializer must be able to deserialize,
+/// and the types only ever returned by actions. Response-only types are just serialized, so
+/// they only need to implement `Serialize`.
+fn collect_service_types(
+    struct_type: &Ident,
+    all_methods: &[(Ident, &str, ImplItemFn)],
+) -> (Vec<String>, Vec<String>) {
+    let mut payload_types = HashSet::new();
+    let mut result_types = HashSet::new();
+
+    for (_, method_type, method) in all_methods {
+        let (mut types, results) = extract_types_from_method(method);
+
+        // Subscriptions converting from a wire type receive that type on the wire,
+        // the handler's own payload type is never serialized
+        if *method_type == "subscribe" {
+            let via = method
+                .attrs
+                .iter()
+                .find(|attr| is_macro_attribute(attr, "subscribe"))
+                .and_then(|attr| attr.parse_args::<SubscribeImpl>().ok())
+                .and_then(|subscribe_impl| subscribe_impl.via);
+            if let Some(via) = via {
+                types = vec![quote! { #via }.to_string()];
+            }
+        }
+
+        for (type_strs, collected) in [(types, &mut payload_types), (results, &mut result_types)] {
+            for type_str in type_strs {
+                if let Some(formatted) = format_type_string(&type_str) {
+                    // Skip the service type itself
+                    if formatted != struct_type.to_string() {
+                        collected.insert(formatted);
+                    }
+                }
+            }
+        }
+    }
+
+    // Convert to vectors and sort for consistent output
+    let mut sorted_payload_types: Vec<_> = payload_types.iter().cloned().collect();
+    sorted_payload_types.sort();
+    let mut sorted_result_types: Vec<_> = result_types
+        .into_iter()
+        .filter(|type_str| !payload_types.contains(type_str))
+        .collect();
+    sorted_result_types.sort();
+    (sorted_payload_types, sorted_result_types)
+}
+
+/// Generate a test checking that every type used by the service survives a
+/// round trip through `ArcValueType`, starting from its `Default` value
+fn generate_type_round_trip_test(
+    struct_type: &Ident,
+    all_methods: &[(Ident, &str, ImplItemFn)],
+) -> TokenStream2 {
+    // Response-only types are never deserialized, so they aren't required to round-trip
+    let (sorted_types, _) = collect_service_types(struct_type, all_methods);
+    let type_tys = sorted_types
+        .iter()
+        .map(|t| {
+            syn::parse_str::<syn::Type>(t).unwrap_or_else(|_| panic!("Failed to parse type: {}", t))
+        })
+        .collect::<Vec<_>>();
+    let module_name = format_ident!("__{}_type_round_trip", struct_type);
+
     quote! {
-        // Static metadata holders
-        static SERVICE_NAME: std::sync::OnceLock<String> = std::sync::OnceLock::new();
-        static SERVICE_PATH: std::sync::OnceLock<String> = std::sync::OnceLock::new();
-        static SERVICE_DESCRIPTION: std::sync::OnceLock<String> = std::sync::OnceLock::new();
-        static SERVICE_VERSION: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod #module_name {
+            use super::*;
+
+            #[test]
+            #[allow(unused_mut)]
+            fn registered_types_round_trip() {
+                let mut failures: Vec<String> = Vec::new();
+                #(
+                    let mut value = runar_common::types::ArcValueType::from_struct(<#type_tys as Default>::default());
+                    if let Err(err) = value.as_type::<#type_tys>() {
+                        failures.push(format!("{}: {}", stringify!(#type_tys), err));
+                    }
+                )*
+                assert!(
+                    failures.is_empty(),
+                    "Types used by service {} don't round-trip through ArcValueType:\n    {}",
+                    stringify!(#struct_type),
+                    failures.join("\n    ")
+                );
+            }
+        }
     }
 }
 
-/// Extract types from a method's parameters and return type
-fn extract_types_from_method(method: &ImplItemFn) -> Vec<String> {
+/// Extract types from a method's parameters and return type, as (parameter types, result types)
+fn extract_types_from_method(method: &ImplItemFn) -> (Vec<String>, Vec<String>) {
     let mut types = Vec::new();
+    let mut result_types = Vec::new();
 
     // Extract parameter types
     for arg in &method.sig.inputs {
-        if let FnArg::Typed(PatType { ty, pat, .. }) = arg {
-            // Skip context parameter
+        if let FnArg::Typed(PatType { ty, pat, attrs, .. }) = arg {
+            // Values read from the context aren't part of the payload
+            if has_marker(attrs, "request_id")
+                || has_marker(attrs, "cancel")
+                || has_marker(attrs, "file")
+            {
+                continue;
+            }
+
+            // Skip context parameter, unless marked with #[param]
             if let Pat::Ident(pat_ident) = &**pat {
                 let param_name = pat_ident.ident.to_string();
-                if param_name == "ctx" || param_name == "context" || param_name.ends_with("ctx") {
+                if !has_marker(attrs, "param")
+                    && (param_name == "ctx"
+                        || param_name == "context"
+                        || param_name.ends_with("ctx"))
+                {
                     continue;
                 }
             }
@@ -138,6 +2117,17 @@ fn extract_types_from_method(method: &ImplItemFn) -> Vec<String> {
             // Get the type as a string
             let type_str = quote! { #ty }.to_string();
             types.push(type_str);
+
+            // Also register the element type of Vec<T> parameters
+            if let Some(element_type) = vec_element_type(ty) {
+                types.push(quote! { #element_type }.to_string());
+            }
+
+            // And the key and value types of map parameters
+            if let Some((key_type, value_type)) = map_key_value_types(ty) {
+                types.push(quote! { #key_type }.to_string());
+                types.push(quote! { #value_type }.to_string());
+            }
         }
     }
 
@@ -153,7 +2143,14 @@ fn extract_types_from_method(method: &ImplItemFn) -> Vec<String> {
                         for arg in &ab.args {
                             if let syn::GenericArgument::Type(ref inner_ty) = arg {
                                 let ok_type_str = quote! { #inner_ty }.to_string();
-                                types.push(ok_type_str);
+                                result_types.push(ok_type_str);
+
+                                // Collect the key and value types of map results
+                                if let Some((key_type, value_type)) = map_key_value_types(inner_ty)
+                                {
+                                    result_types.push(quote! { #key_type }.to_string());
+                                    result_types.push(quote! { #value_type }.to_string());
+                                }
                                 break;
                             }
                         }
@@ -161,16 +2158,16 @@ fn extract_types_from_method(method: &ImplItemFn) -> Vec<String> {
                 } else {
                     // Not a Result, just add the type directly
                     let return_type_str = quote! { #ty }.to_string();
-                    types.push(return_type_str);
+                    result_types.push(return_type_str);
                 }
             }
         } else {
             // For non-Result types, just add the type directly
-            types.push(quote! { #ty }.to_string());
+            result_types.push(quote! { #ty }.to_string());
         }
     }
 
-    types
+    (types, result_types)
 }
 
 /// Format type string to be more readable and filter out standard types
@@ -190,7 +2187,13 @@ fn format_type_string(type_str: &str) -> Option<String> {
     match formatted.as_str() {
         // Primitive types
         "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
-        | "usize" | "f32" | "f64" | "bool" | "char" | "()" | "String" => None,
+        | "usize" | "f32" | "f64" | "bool" | "char" | "()" | "String" | "str" => None,
+        // Already a runtime value, nothing to register
+        _ if formatted.ends_with("ArcValueType") => None,
+        // Cow<str> is extracted as a String
+        _ if is_cow_str_type(&formatted) => None,
+        // Binary payloads are sent as raw bytes
+        _ if is_bytes_type(&formatted) => None,
         _ => Some(formatted),
     }
 }
@@ -219,19 +2222,61 @@ fn is_primitive_type(type_str: &str) -> bool {
     )
 }
 
+/// Resolve the path an action is registered at, or the topic a subscription listens on
+fn registration_path(method_name: &Ident, method_type: &str, method: &ImplItemFn) -> String {
+    if method_type == "action" {
+        // Attribute errors are reported by the action macro itself
+        let action_impl = find_action_attribute(method)
+            .and_then(|attr| parse_action_attribute(attr).ok())
+            .unwrap_or_default();
+        let action_name = action_impl.name.unwrap_or_else(|| method_name.to_string());
+        action_impl.path.unwrap_or(action_name)
+    } else {
+        method
+            .attrs
+            .iter()
+            .find(|attr| is_macro_attribute(attr, "subscribe"))
+            .and_then(|attr| attr.parse_args::<SubscribeImpl>().ok())
+            .map(|subscribe_impl| subscribe_impl.path.value())
+            .unwrap_or_else(|| method_name.to_string())
+    }
+}
+
 /// Generate the AbstractService trait implementation
 /// Ensure the struct implements Clone for proper action handler support
 fn generate_abstract_service_impl(
     struct_type: &Ident,
     all_methods: &[(Ident, &str, ImplItemFn)],
-    service_attrs: &HashMap<String, String>,
+    service_attrs: &ServiceAttributes,
 ) -> TokenStream2 {
+    // Register in path order so init behavior and logs don't depend on the method order
+    let mut ordered_methods = all_methods.iter().collect::<Vec<_>>();
+    ordered_methods.sort_by_cached_key(|(method_name, method_type, method)| {
+        registration_path(method_name, method_type, method)
+    });
+
     // Create method identifiers for action registration
-    let method_registrations = all_methods.iter().map(|(method_name, method_type, _)| {
+    let method_registrations = ordered_methods.iter().map(|(method_name, method_type, method)| {
         if *method_type == "action" {
             let register_method_name = format_ident!("register_action_{}", method_name);
-            quote! {
-                self.#register_method_name(context_ref).await?;
+
+            // Actions with an enabled_if predicate are only registered when it holds at init time.
+            // Attribute errors are reported by the action macro itself.
+            let enabled_if = find_action_attribute(method)
+                .and_then(|attr| parse_action_attribute(attr).ok())
+                .and_then(|action_impl| action_impl.enabled_if);
+
+            match enabled_if {
+                Some(predicate) => quote! {
+                    if #predicate {
+                        self.#register_method_name(context_ref).await?;
+                    } else {
+                        context_ref.info(format!("Skipping disabled action '{}'", stringify!(#method_name)));
+                    }
+                },
+                None => quote! {
+                    self.#register_method_name(context_ref).await?;
+                },
             }
         } else {
             // Must be a subscription
@@ -270,27 +2315,104 @@ fn generate_abstract_service_impl(
         .cloned()
         .unwrap_or_else(|| "1.0.0".to_string());
 
-    // Extract all types from methods
-    let mut all_types = HashSet::new();
+    // Values from a `config = ...` const are only available at runtime, so they are read
+    // in the metadata initializers. Inline attributes take precedence over the config.
+    let metadata_init =
+        |key: &str, default_value: &String| match (&service_attrs.config, service_attrs.get(key)) {
+            (Some(config), None) => {
+                let field = format_ident!("{}", key);
+                quote! { #config.#field.to_string() }
+            }
+            _ => quote! { #default_value.to_string() },
+        };
+    let name_init = metadata_init("name", &name_value);
+    let path_init = metadata_init("path", &path_value);
+    let description_init = metadata_init("description", &description_value);
+    let version_init = metadata_init("version", &version_value);
+
+    // Register the built-in health action, which measures its uptime from init
+    let health_registration = if service_attrs.flag("health") {
+        quote! { self.register_health_action(context_ref).await?; }
+    } else {
+        TokenStream2::new()
+    };
+
+    // Run the warmup hooks when the service starts, after every service's init
+    let (start_context, warmup_call) = if service_attrs.flag("warmup") {
+        (
+            format_ident!("context"),
+            quote! { self.warmup(&context).await?; },
+        )
+    } else {
+        (format_ident!("_context"), TokenStream2::new())
+    };
+
+    // Guard against a second instance of a singleton service being initialized
+    let (singleton_static, singleton_guard, singleton_commit, singleton_release) = if service_attrs
+        .flag("singleton")
+    {
+        (
+            quote! {
+                // Set while an instance of the singleton service is initialized
+                static SERVICE_SINGLETON_INITIALIZED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+            },
+            quote! {
+                if SERVICE_SINGLETON_INITIALIZED
+                    .compare_exchange(false, true, std::sync::atomic::Ordering::SeqCst, std::sync::atomic::Ordering::SeqCst)
+                    .is_err()
+                {
+                    return Err(anyhow::anyhow!(format!(
+                        "Service {} is a singleton and an instance has already been initialized",
+                        stringify!(#struct_type)
+                    )));
+                }
 
-    for (_, _, method) in all_methods {
-        let types = extract_types_from_method(method);
-        for type_str in types {
-            if let Some(formatted) = format_type_string(&type_str) {
-                // Skip the service type itself
-                if formatted != struct_type.to_string() {
-                    all_types.insert(formatted);
+                // Clears the flag again if init fails, disarmed once init succeeds
+                struct SingletonInitGuard {
+                    armed: bool,
                 }
-            }
-        }
-    }
 
-    // Convert to a vector and sort for consistent output
-    let mut sorted_types: Vec<_> = all_types.into_iter().collect();
-    sorted_types.sort();
+                impl Drop for SingletonInitGuard {
+                    fn drop(&mut self) {
+                        if self.armed {
+                            SERVICE_SINGLETON_INITIALIZED.store(false, std::sync::atomic::Ordering::SeqCst);
+                        }
+                    }
+                }
+
+                let mut singleton_init_guard = SingletonInitGuard { armed: true };
+            },
+            quote! {
+                singleton_init_guard.armed = false;
+            },
+            quote! {
+                // Allow a new instance once this one has stopped
+                SERVICE_SINGLETON_INITIALIZED.store(false, std::sync::atomic::Ordering::SeqCst);
+            },
+        )
+    } else {
+        (
+            TokenStream2::new(),
+            TokenStream2::new(),
+            TokenStream2::new(),
+            TokenStream2::new(),
+        )
+    };
+
+    // Extract all types from methods, only payload types are registered for deserialization
+    let (sorted_types, response_only_types) = collect_service_types(struct_type, all_methods);
 
     // Create a string literal with all the types
-    let types_str = sorted_types.join("\n");
+    let types_str = sorted_types
+        .iter()
+        .cloned()
+        .chain(
+            response_only_types
+                .iter()
+                .map(|type_str| format!("{} (response only)", type_str)),
+        )
+        .collect::<Vec<_>>()
+        .join("\n");
 
     // Create type identifiers for each type
     let type_idents = sorted_types
@@ -309,29 +2431,41 @@ fn generate_abstract_service_impl(
     };
 
     quote! {
+        #singleton_static
+
         #[async_trait::async_trait]
-        impl runar_node::services::abstract_service::AbstractService  for #struct_type {
+        // The bound is higher-ranked so rustc doesn't check it eagerly: a service that isn't
+        // Send + Sync is reported once, by the assertion, instead of by every generated item
+        impl runar_node::services::abstract_service::AbstractService for #struct_type
+        where
+            for<'__service> #struct_type: Send + Sync,
+        {
+            // The metadata accessors are called per request for routing, so they're inlined
+            #[inline]
             fn name(&self) -> &str {
                 SERVICE_NAME.get_or_init(|| {
-                    #name_value.to_string()
+                    #name_init
                 })
             }
 
+            #[inline]
             fn path(&self) -> &str {
                 SERVICE_PATH.get_or_init(|| {
-                    #path_value.to_string()
+                    #path_init
                 })
             }
 
+            #[inline]
             fn description(&self) -> &str {
                 SERVICE_DESCRIPTION.get_or_init(|| {
-                    #description_value.to_string()
+                    #description_init
                 })
             }
 
+            #[inline]
             fn version(&self) -> &str {
                 SERVICE_VERSION.get_or_init(|| {
-                    #version_value.to_string()
+                    #version_init
                 })
             }
 
@@ -342,23 +2476,30 @@ fn generate_abstract_service_impl(
             }
 
             async fn init(&self, context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                #singleton_guard
+
                 // Create a reference to the context
                 let context_ref = &context;
 
                 // Register all action and subscription methods defined with the #[action] or #[subscribe] macro
                 #(#method_registrations)*
 
+                #health_registration
+
                 // Register complex types with the serializer
                 Self::register_types(context_ref).await?;
 
+                #singleton_commit
                 Ok(())
             }
 
-            async fn start(&self, _context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+            async fn start(&self, #start_context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                #warmup_call
                 Ok(())
             }
 
             async fn stop(&self, _context: runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                #singleton_release
                 Ok(())
             }
         }
@@ -398,9 +2539,14 @@ fn generate_abstract_service_impl(
                     context.debug(format!("Registering type: {}", stringify!(#type_idents)));
                 })*
                 // Print all types being registered for macro transparency
-                context.debug(format!("All types registered: [{}]", [#(stringify!(#type_idents)),*].join(", ")));
+                context.debug(format!("All types registered: [{}]", <[&str]>::join(&[#(stringify!(#type_idents)),*], ", ")));
+                // Types shared with another service may already be registered, so they are skipped
                 #({
-                    serializer.register::<#type_idents>()?;
+                    if serializer.is_registered::<#type_idents>() {
+                        context.debug(format!("Type {} is already registered", stringify!(#type_idents)));
+                    } else {
+                        serializer.register::<#type_idents>()?;
+                    }
                 })*
 
                 Ok(())
@@ -4,39 +4,207 @@
 // of a Runar service event subscription by automatically generating handler code for
 // parameter extraction and event handling.
 
+use crate::utils::{
+    expect_lit_bool, expect_lit_str, expect_positive_int, extract_result_ok_type, has_marker,
+    is_cow_str_type, strip_markers,
+};
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use syn::{
-    parse::Parse, parse::ParseStream, parse_macro_input, Expr, FnArg, Ident, ItemFn, Lit, LitStr,
-    Meta, Pat, PatIdent, PatType, Result, Token, Type,
+    parse::Parse, parse::ParseStream, parse_macro_input, punctuated::Punctuated, Attribute, Expr,
+    FnArg, Ident, ItemFn, LitBool, LitInt, LitStr, Meta, Pat, PatIdent, PatType, Result, Token,
+    Type,
 };
 
 // Define a struct to parse the macro attributes
 pub struct SubscribeImpl {
     pub path: LitStr,
     pub handler: Option<Expr>,
+    /// Payload field used to serialize handler invocations per key
+    pub ordered_by: Option<LitStr>,
+    /// Maximum number of handler invocations running at the same time
+    pub max_concurrency: Option<LitInt>,
+    /// Wire type the event is deserialized into before `TryFrom` conversion to the payload type
+    pub via: Option<Type>,
+    /// Whether the subscription starts paused, generating pause_/resume_ methods when set
+    pub start_paused: Option<LitBool>,
+    /// What happens when events waiting for a permit fill the buffer, `block` or `drop_oldest`
+    pub buffer_full: Option<LitStr>,
+    /// Number of events allowed to wait for a permit before the oldest is dropped
+    pub buffer_size: Option<LitInt>,
+    /// Whether a panic in the handler is caught and reported as a handler error
+    pub catch_panics: Option<LitBool>,
+    /// External store, shared by all service instances, used to detect duplicate events
+    pub dedup_store: Option<LitStr>,
+    /// Payload field identifying an event for duplicate detection
+    pub dedup_key: Option<LitStr>,
+    /// Service field the handler's return value is stored into
+    pub store_in: Option<LitStr>,
+}
+
+// LitStr has no Default, so the path is a placeholder that every parsed subscription replaces
+impl Default for SubscribeImpl {
+    fn default() -> Self {
+        SubscribeImpl {
+            path: LitStr::new("", proc_macro2::Span::call_site()),
+            handler: None,
+            ordered_by: None,
+            max_concurrency: None,
+            via: None,
+            start_paused: None,
+            buffer_full: None,
+            buffer_size: None,
+            catch_panics: None,
+            dedup_store: None,
+            dedup_key: None,
+            store_in: None,
+        }
+    }
 }
 
 impl Parse for SubscribeImpl {
     fn parse(input: ParseStream) -> Result<Self> {
-        // Check if we have path="value" format
+        // Check if we have path="value" format, optionally followed by more options
         if input.peek(syn::Ident) {
-            let meta = input.parse::<Meta>()?;
-            if let Meta::NameValue(name_value) = meta {
+            let mut path = None;
+            let mut ordered_by = None;
+            let mut max_concurrency = None;
+            let mut via = None;
+            let mut start_paused = None;
+            let mut buffer_full = None;
+            let mut buffer_size = None;
+            let mut catch_panics = None;
+            let mut dedup_store = None;
+            let mut dedup_key = None;
+            let mut store_in = None;
+
+            let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+            for meta in metas {
+                let Meta::NameValue(name_value) = &meta else {
+                    return Err(syn::Error::new_spanned(
+                        &meta,
+                        "Expected path=\"value\" or a string literal",
+                    ));
+                };
+
                 if name_value.path.is_ident("path") {
-                    // Extract the string literal from the expression
-                    if let Expr::Lit(expr_lit) = &name_value.value {
-                        if let Lit::Str(lit_str) = &expr_lit.lit {
-                            return Ok(SubscribeImpl {
-                                path: lit_str.clone(),
-                                handler: None,
-                            });
+                    path = Some(expect_lit_str(&name_value.value)?);
+                } else if name_value.path.is_ident("ordered_by") {
+                    ordered_by = Some(expect_lit_str(&name_value.value)?);
+                } else if name_value.path.is_ident("max_concurrency") {
+                    max_concurrency = Some(expect_positive_int::<usize>(&name_value.value)?);
+                } else if name_value.path.is_ident("via") {
+                    via = Some(match &name_value.value {
+                        Expr::Path(expr_path) if expr_path.qself.is_none() => {
+                            Type::Path(syn::TypePath {
+                                qself: None,
+                                path: expr_path.path.clone(),
+                            })
+                        }
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "Expected the wire type to convert from, e.g. via = RawEvent",
+                            ))
                         }
+                    });
+                } else if name_value.path.is_ident("start_paused") {
+                    start_paused = Some(expect_lit_bool(&name_value.value, "start_paused")?);
+                } else if name_value.path.is_ident("buffer_full") {
+                    let policy = expect_lit_str(&name_value.value)?;
+                    if !matches!(policy.value().as_str(), "block" | "drop_oldest") {
+                        return Err(syn::Error::new_spanned(
+                            &policy,
+                            "Unknown buffer_full policy, expected \"block\" or \"drop_oldest\"",
+                        ));
+                    }
+                    buffer_full = Some(policy);
+                } else if name_value.path.is_ident("catch_panics") {
+                    catch_panics = Some(expect_lit_bool(&name_value.value, "catch_panics")?);
+                } else if name_value.path.is_ident("dedup_store") {
+                    dedup_store = Some(expect_lit_str(&name_value.value)?);
+                } else if name_value.path.is_ident("dedup_key") {
+                    dedup_key = Some(expect_lit_str(&name_value.value)?);
+                } else if name_value.path.is_ident("store_in") {
+                    let field = expect_lit_str(&name_value.value)?;
+                    if syn::parse_str::<Ident>(&field.value()).is_err() {
+                        return Err(syn::Error::new_spanned(
+                            &field,
+                            "Expected the name of a service field, e.g. store_in = \"last_value\"",
+                        ));
                     }
+                    store_in = Some(field);
+                } else if name_value.path.is_ident("buffer_size") {
+                    buffer_size = Some(expect_positive_int::<usize>(&name_value.value)?);
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.path,
+                        "Unknown subscribe option, expected one of: path, ordered_by, max_concurrency, via, start_paused, buffer_full, buffer_size, catch_panics, dedup_store, dedup_key, store_in",
+                    ));
+                }
+            }
+
+            // Events only wait in a buffer while they wait for a max_concurrency permit
+            let drops_oldest = buffer_full
+                .as_ref()
+                .is_some_and(|policy| policy.value() == "drop_oldest");
+            match (&buffer_full, &buffer_size) {
+                (Some(policy), _) if max_concurrency.is_none() => {
+                    return Err(syn::Error::new_spanned(
+                        policy,
+                        "buffer_full requires max_concurrency, events only wait in the buffer for a permit",
+                    ))
+                }
+                (Some(policy), None) if drops_oldest => {
+                    return Err(syn::Error::new_spanned(
+                        policy,
+                        "buffer_full = \"drop_oldest\" requires buffer_size",
+                    ))
+                }
+                (_, Some(size)) if !drops_oldest => {
+                    return Err(syn::Error::new_spanned(
+                        size,
+                        "buffer_size requires buffer_full = \"drop_oldest\"",
+                    ))
                 }
+                _ => {}
             }
-            return Err(input.error("Expected path=\"value\" or a string literal"));
+
+            // Duplicates are detected by key in the store, so one is useless without the other
+            match (&dedup_store, &dedup_key) {
+                (Some(store), None) => {
+                    return Err(syn::Error::new_spanned(
+                        store,
+                        "dedup_store requires dedup_key, the payload field identifying an event",
+                    ))
+                }
+                (None, Some(key)) => {
+                    return Err(syn::Error::new_spanned(
+                        key,
+                        "dedup_key requires dedup_store, the store shared by the service instances",
+                    ))
+                }
+                _ => {}
+            }
+
+            return match path {
+                Some(path) => Ok(SubscribeImpl {
+                    path,
+                    ordered_by,
+                    max_concurrency,
+                    via,
+                    start_paused,
+                    buffer_full,
+                    buffer_size,
+                    catch_panics,
+                    dedup_store,
+                    dedup_key,
+                    store_in,
+                    ..Default::default()
+                }),
+                None => Err(input.error("Expected path=\"value\" or a string literal")),
+            };
         }
 
         // Otherwise, try to parse as a string literal followed by a handler
@@ -49,12 +217,13 @@ impl Parse for SubscribeImpl {
             Ok(SubscribeImpl {
                 path,
                 handler: Some(handler),
+                ..Default::default()
             })
         } else {
             // Just a path string
             Ok(SubscribeImpl {
                 path,
-                handler: None,
+                ..Default::default()
             })
         }
     }
@@ -63,58 +232,561 @@ impl Parse for SubscribeImpl {
 /// Implementation of the subscribe macro
 pub fn subscribe_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Parse the input as a function
-    let input = parse_macro_input!(item as ItemFn);
+    let mut input = parse_macro_input!(item as ItemFn);
 
     // Parse the attributes
     let subscribe_impl = parse_macro_input!(attr as SubscribeImpl);
     let path = &subscribe_impl.path;
     let path_value = &path.value();
 
+    // Subscription handlers are called with the event context
+    if let Err(err) = validate_context_parameter(&input) {
+        return err.to_compile_error().into();
+    }
+
+    // Extract parameters from the function signature, `{name}` path captures aren't payload
+    let captures = path_captures(path_value);
+    let (capture_params, params): (Vec<_>, Vec<_>) = extract_parameters(&input)
+        .into_iter()
+        .partition(|(param_ident, _)| captures.iter().any(|(_, name)| param_ident == name));
+    let meta_params = match extract_event_meta_parameters(&input) {
+        Ok(meta_params) => meta_params,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let call_args = generate_call_arguments(&input);
+    strip_event_meta_attributes(&mut input);
+    strip_markers(&mut input.sig, &["param"]);
+
     // Get the function identifier
     let fn_ident = &input.sig.ident;
-    let attrs = &input.attrs;
-    let vis = &input.vis;
 
-    // Extract parameters from the function signature
-    let params = extract_parameters(&input);
+    // Generate code to read #[from_event_meta] parameters from the event context
+    let meta_extractions = meta_params.iter().map(|(param_ident, param_type, key)| {
+        quote! {
+            let #param_ident: #param_type = match ctx.get_metadata(#key) {
+                Some(value) => match value.parse::<#param_type>() {
+                    Ok(val) => val,
+                    Err(err) => {
+                        return Err(anyhow::anyhow!(format!("Failed to parse event metadata {} as {}: {}", #key, stringify!(#param_type), err)));
+                    }
+                },
+                None => {
+                    return Err(anyhow::anyhow!(format!("Event metadata {} is missing for {}", #key, #path_value)));
+                }
+            };
+        }
+    });
+    let meta_extractions = quote! { #(#meta_extractions)* };
+
+    // Every placeholder in the path must be bound to a String parameter
+    if let Some((_, name)) = captures
+        .iter()
+        .find(|(_, name)| !capture_params.iter().any(|(ident, _)| ident == name))
+    {
+        return syn::Error::new_spanned(
+            path,
+            format!(
+                "Path placeholder `{{{}}}` has no matching handler parameter",
+                name
+            ),
+        )
+        .to_compile_error()
+        .into();
+    }
+    if let Some((_, param_type)) = capture_params
+        .iter()
+        .find(|(_, param_type)| quote! { #param_type }.to_string() != "String")
+    {
+        return syn::Error::new_spanned(param_type, "Path captures are bound as `String`")
+            .to_compile_error()
+            .into();
+    }
+    if !captures.is_empty() && path_value.split('/').any(|segment| segment == ">") {
+        return syn::Error::new_spanned(
+            path,
+            "Path placeholders can't be combined with the `>` wildcard",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Subscribe with a wildcard for each placeholder and read the captures from the firing topic
+    let subscription_path = path_value
+        .split('/')
+        .map(|segment| {
+            if is_placeholder(segment) {
+                "*"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    let capture_extractions = if captures.is_empty() {
+        TokenStream2::new()
+    } else {
+        let segment_count = path_value.split('/').count();
+        let capture_extractions = captures.iter().map(|(index, name)| {
+            let param_ident = format_ident!("{}", name);
+            quote! {
+                let #param_ident = topic_segments[topic_offset + #index].to_string();
+            }
+        });
+        quote! {
+            // The template matches the trailing segments of the topic, after any network prefix
+            let topic = ctx.topic_path.as_str().to_string();
+            let topic_segments = topic
+                .rsplit_once(':')
+                .map_or(topic.as_str(), |(_, topic_path)| topic_path)
+                .split('/')
+                .collect::<Vec<_>>();
+            let topic_offset = match topic_segments.len().checked_sub(#segment_count) {
+                Some(offset) => offset,
+                None => {
+                    return Err(anyhow::anyhow!(format!("Topic {} does not match {}", topic, #path_value)));
+                }
+            };
+            #(#capture_extractions)*
+        }
+    };
+    let meta_extractions = quote! {
+        #capture_extractions
+        #meta_extractions
+    };
 
     // Generate a unique method name for the subscription registration
     let register_method_name = format_ident!("register_subscription_{}", fn_ident);
 
+    // Ordering by key needs a payload to read the key from
+    if let (Some(ordered_by), true) = (&subscribe_impl.ordered_by, params.len() != 1) {
+        return syn::Error::new_spanned(
+            ordered_by,
+            "ordered_by requires the handler to take exactly one payload parameter",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Deduplicating by key needs a payload to read the key from
+    if let (Some(dedup_key), true) = (&subscribe_impl.dedup_key, params.len() != 1) {
+        return syn::Error::new_spanned(
+            dedup_key,
+            "dedup_key requires the handler to take exactly one payload parameter",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Converting from a wire type needs a payload to convert into
+    if let (Some(via), true) = (&subscribe_impl.via, params.len() != 1) {
+        return syn::Error::new_spanned(
+            via,
+            "via requires the handler to take exactly one payload parameter",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // Storing the handler's result needs a result to store
+    if let Some(store_in) = &subscribe_impl.store_in {
+        let returns_value = extract_result_ok_type(&input.sig.output).is_some_and(
+            |ok_type| !matches!(&ok_type, Type::Tuple(tuple) if tuple.elems.is_empty()),
+        );
+        if !returns_value {
+            return syn::Error::new_spanned(
+                store_in,
+                "store_in requires the handler to return the value to store, e.g. Result<u64>",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    // Store the handler's result into the service field, a missing field is reported on the option
+    let handler_ok = match &subscribe_impl.store_in {
+        Some(store_in) => {
+            let field = format_ident!("{}", store_in.value(), span = store_in.span());
+            quote! {
+                Ok(value) => {
+                    *self_clone.#field.write().await = value;
+                    Ok(())
+                },
+            }
+        }
+        None => quote! { Ok(_) => Ok(()), },
+    };
+
+    // Generate the per-key queues and the slot taken in them for ordered subscriptions
+    let (order_setup, order_slot, order_acquire) = match (
+        &subscribe_impl.ordered_by,
+        params.first(),
+    ) {
+        (Some(ordered_by), Some(_)) => {
+            let key_field = format_ident!("{}", ordered_by.value());
+            (
+                quote! {
+                    // Each event waits for the previous event with the same key, the map holds the
+                    // receiver signalled when the last queued event of every key in use is done
+                    type OrderQueues = std::sync::Arc<std::sync::Mutex<(u64, std::collections::HashMap<String, (u64, tokio::sync::oneshot::Receiver<()>)>)>>;
+
+                    struct OrderSlot {
+                        queues: OrderQueues,
+                        key: String,
+                        ticket: u64,
+                        previous: Option<tokio::sync::oneshot::Receiver<()>>,
+                        done: Option<tokio::sync::oneshot::Sender<()>>,
+                    }
+
+                    impl OrderSlot {
+                        // Queue behind the last event with the same key, without awaiting
+                        fn take(queues: &OrderQueues, key: String) -> Self {
+                            let (done, next) = tokio::sync::oneshot::channel();
+                            let mut state = queues.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                            state.0 += 1;
+                            let ticket = state.0;
+                            let previous = state.1.insert(key.clone(), (ticket, next)).map(|(_, previous)| previous);
+                            OrderSlot {
+                                queues: queues.clone(),
+                                key,
+                                ticket,
+                                previous,
+                                done: Some(done),
+                            }
+                        }
+
+                        async fn wait(&mut self) {
+                            if let Some(previous) = self.previous.take() {
+                                // The previous event is done once its sender is dropped
+                                let _ = previous.await;
+                            }
+                        }
+                    }
+
+                    impl Drop for OrderSlot {
+                        // Wakes the next event with the same key, then drops the key's entry if no
+                        // event is queued behind this one, so the map only keeps the keys in use
+                        fn drop(&mut self) {
+                            self.done.take();
+                            let mut state = self.queues.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                            if state.1.get(&self.key).is_some_and(|(ticket, _)| *ticket == self.ticket) {
+                                state.1.remove(&self.key);
+                            }
+                        }
+                    }
+
+                    let order_queues: OrderQueues = std::sync::Arc::new(std::sync::Mutex::new((0, std::collections::HashMap::new())));
+                },
+                quote! {
+                    // Take the event's place in its key's queue as it is delivered, before any await,
+                    // so events with the same key are handled in the order they arrive
+                    let mut order_slot = payload
+                        .as_ref()
+                        .ok()
+                        .map(|payload| OrderSlot::take(&order_queues, payload.#key_field.to_string()));
+                },
+                quote! {
+                    // Wait for the previous event with the same key
+                    if let Some(order_slot) = order_slot.as_mut() {
+                        order_slot.wait().await;
+                    }
+                },
+            )
+        }
+        _ => (
+            TokenStream2::new(),
+            TokenStream2::new(),
+            TokenStream2::new(),
+        ),
+    };
+
+    // Ask the node whether another instance already handled the event, the store itself
+    // is provided by the node
+    let dedup_check = match (
+        &subscribe_impl.dedup_store,
+        &subscribe_impl.dedup_key,
+        params.first(),
+    ) {
+        (Some(dedup_store), Some(dedup_key), Some((param_ident, _))) => {
+            let key_field = format_ident!("{}", dedup_key.value());
+            quote! {
+                // Skip events already recorded in the shared store
+                let dedup_key = #param_ident.#key_field.to_string();
+                match ctx.dedup_check(#dedup_store, &dedup_key).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        ctx.debug(format!("Skipping duplicate event {} for {}", dedup_key, #path_value));
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        return Err(anyhow::anyhow!(format!("Failed to check event {} for {} against {}: {}", dedup_key, #path_value, #dedup_store, err)));
+                    }
+                }
+            }
+        }
+        _ => TokenStream2::new(),
+    };
+
+    // Generate the semaphore limiting concurrent handler invocations
+    let (concurrency_setup, concurrency_clone, concurrency_acquire) = match (
+        &subscribe_impl.max_concurrency,
+        &subscribe_impl.buffer_size,
+    ) {
+        (Some(max_concurrency), Some(buffer_size)) => (
+            quote! {
+                // At most buffer_size events wait for a permit, the oldest is dropped to make room
+                let concurrency_limit = std::sync::Arc::new(tokio::sync::Semaphore::new(#max_concurrency));
+                let waiting_events: std::sync::Arc<std::sync::Mutex<std::collections::VecDeque<std::sync::Arc<tokio::sync::Notify>>>> =
+                    std::sync::Arc::new(std::sync::Mutex::new(std::collections::VecDeque::new()));
+            },
+            quote! {
+                let concurrency_limit = concurrency_limit.clone();
+                let waiting_events = waiting_events.clone();
+            },
+            quote! {
+                // Join the buffer, notifying the oldest waiting event when it overflows
+                let dropped = std::sync::Arc::new(tokio::sync::Notify::new());
+                {
+                    let mut waiting = waiting_events.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    waiting.push_back(dropped.clone());
+                    if waiting.len() > #buffer_size {
+                        if let Some(oldest) = waiting.pop_front() {
+                            oldest.notify_one();
+                        }
+                    }
+                }
+                let _concurrency_permit = tokio::select! {
+                    permit = concurrency_limit.acquire() => match permit {
+                        Ok(permit) => permit,
+                        Err(err) => {
+                            return Err(anyhow::anyhow!(format!("Failed to acquire handler permit for {}: {}", #path_value, err)));
+                        }
+                    },
+                    _ = dropped.notified() => {
+                        ctx.debug(format!("Buffer full, dropping the oldest waiting event for {}", #path_value));
+                        return Ok(());
+                    }
+                };
+                waiting_events
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .retain(|waiting| !std::sync::Arc::ptr_eq(waiting, &dropped));
+            },
+        ),
+        (Some(max_concurrency), None) => (
+            quote! {
+                // Events beyond the limit wait for a permit instead of being dropped
+                let concurrency_limit = std::sync::Arc::new(tokio::sync::Semaphore::new(#max_concurrency));
+            },
+            quote! {
+                let concurrency_limit = concurrency_limit.clone();
+            },
+            quote! {
+                let _concurrency_permit = match concurrency_limit.acquire().await {
+                    Ok(permit) => permit,
+                    Err(err) => {
+                        return Err(anyhow::anyhow!(format!("Failed to acquire handler permit for {}: {}", #path_value, err)));
+                    }
+                };
+            },
+        ),
+        (None, _) => (
+            TokenStream2::new(),
+            TokenStream2::new(),
+            TokenStream2::new(),
+        ),
+    };
+
+    // Gate event delivery behind a pause flag that can be flipped at runtime
+    let gate_method_name = format_ident!("subscription_paused_{}", fn_ident);
+    let (pause_check, pause_reset, pause_methods) = match &subscribe_impl.start_paused {
+        Some(start_paused) => {
+            let pause_method_name = format_ident!("pause_{}", fn_ident);
+            let resume_method_name = format_ident!("resume_{}", fn_ident);
+            // The flag belongs to the service type, so the methods don't take an instance
+            let pause_doc = format!(
+                "Stop delivering `{}` events to `{}` for every instance of the service, events published while paused are dropped",
+                path_value, fn_ident
+            );
+            let resume_doc = format!(
+                "Start delivering `{}` events to `{}` for every instance of the service",
+                path_value, fn_ident
+            );
+            (
+                quote! {
+                    // Events published while the subscription is paused are dropped
+                    if Self::#gate_method_name().load(std::sync::atomic::Ordering::Acquire) {
+                        ctx.debug(format!("Dropping event for paused subscription {}", #path_value));
+                        return Ok(());
+                    }
+                },
+                quote! {
+                    // Every instance starts in the declared state, whatever an earlier one left behind
+                    Self::#gate_method_name().store(#start_paused, std::sync::atomic::Ordering::Release);
+                },
+                quote! {
+                    #[doc(hidden)]
+                    fn #gate_method_name() -> &'static std::sync::atomic::AtomicBool {
+                        static PAUSED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(#start_paused);
+                        &PAUSED
+                    }
+
+                    #[doc = #pause_doc]
+                    pub fn #pause_method_name() {
+                        Self::#gate_method_name().store(true, std::sync::atomic::Ordering::Release);
+                    }
+
+                    #[doc = #resume_doc]
+                    pub fn #resume_method_name() {
+                        Self::#gate_method_name().store(false, std::sync::atomic::Ordering::Release);
+                    }
+                },
+            )
+        }
+        None => (
+            TokenStream2::new(),
+            TokenStream2::new(),
+            TokenStream2::new(),
+        ),
+    };
+
+    // With the `tracing` feature every handler invocation runs in a span carrying the topic
+    let handler_call = quote! { self_clone.#fn_ident(#(#call_args),*) };
+    let handler_call = if cfg!(feature = "tracing") {
+        quote! {
+            tracing::Instrument::instrument(
+                #handler_call,
+                tracing::info_span!(
+                    "subscription",
+                    topic = %ctx.topic_path.as_str(),
+                    handler = stringify!(#fn_ident),
+                ),
+            )
+        }
+    } else {
+        handler_call
+    };
+
+    // With catch_panics = true a panicking handler is reported like a handler error. The
+    // future is polled through `futures::FutureExt::catch_unwind`, so the user crate must
+    // depend on `futures`. The panic is logged here unless the error branch logs it anyway.
+    let catch_panics = subscribe_impl
+        .catch_panics
+        .as_ref()
+        .is_some_and(|catch_panics| catch_panics.value);
+    let guarded_call = |log_panic: bool| {
+        if !catch_panics {
+            return quote! { #handler_call.await };
+        }
+        let panic_log = if log_panic {
+            quote! {
+                ctx.error(format!("Event handler for {} panicked: {}", #path_value, message));
+            }
+        } else {
+            TokenStream2::new()
+        };
+        quote! {
+            match futures::FutureExt::catch_unwind(std::panic::AssertUnwindSafe(#handler_call)).await {
+                Ok(result) => result,
+                Err(panic) => {
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(|message| message.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "unknown panic payload".to_string());
+                    #panic_log
+                    Err(anyhow::anyhow!(format!("handler panicked: {}", message)))
+                }
+            }
+        }
+    };
+
     // Generate the registration method based on parameters
     let register_method = if params.len() == 1 {
         let (param_ident, param_type) = &params[0];
+        // Errors are returned to the node, so a caught panic is logged here
+        let handler_call = guarded_call(true);
+
+        // Primitives are read like single-parameter actions read them, string views as an owned String
+        let type_str = quote! { #param_type }.to_string();
+        let (value_type, wrap) = if is_cow_str_type(&type_str) {
+            (quote! { String }, quote! { std::borrow::Cow::Owned(val) })
+        } else if type_str == "& str" {
+            (quote! { String }, quote! { val })
+        } else {
+            (quote! { #param_type }, quote! { val })
+        };
+
+        // Deserialize into the payload type, or into the wire type and convert it
+        let payload_extraction = match &subscribe_impl.via {
+            Some(via) => quote! {
+                let payload = match value {
+                    Some(value) => match value.clone().as_type::<#via>() {
+                        Ok(raw) => match <#param_type as std::convert::TryFrom<#via>>::try_from(raw) {
+                            Ok(val) => Ok(val),
+                            Err(err) => Err(anyhow::anyhow!(format!("Failed to convert event value from {} to {}: {}", stringify!(#via), stringify!(#param_type), err))),
+                        },
+                        Err(err) => Err(anyhow::anyhow!(format!("Failed to parse event value as {}: {}", stringify!(#via), err))),
+                    },
+                    None => Err(anyhow::anyhow!(format!("Required event value is missing for {}", #path_value))),
+                };
+            },
+            None => quote! {
+                let payload = match value {
+                    Some(value) => match value.clone().as_type::<#value_type>() {
+                        Ok(val) => Ok(#wrap),
+                        Err(err) => Err(anyhow::anyhow!(format!("Failed to parse event value as {}: {}", stringify!(#param_type), err))),
+                    },
+                    None => Err(anyhow::anyhow!(format!("Required event value is missing for {}", #path_value))),
+                };
+            },
+        };
+
         quote! {
-            async fn #register_method_name(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+            #[doc(hidden)]
+            async fn #register_method_name(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()>
+            where
+                // A service that isn't thread-safe is only reported by the service's Send + Sync assertion
+                for<'__service> Self: Send + Sync,
+            {
                 context.info(format!("Subscribing to '{}' event", #path_value));
+                #pause_reset
 
                 // Create a clone of self that can be moved into the closure
                 let self_clone = self.clone();
 
+                #order_setup
+                #concurrency_setup
+
                 // Register the event handler
-                context.subscribe(#path, Box::new(move |ctx, value| {
+                context.subscribe(#subscription_path, Box::new(move |ctx, value| {
                     // Create a boxed future that returns Result<(), anyhow::Error>
                     let self_clone = self_clone.clone();
+                    #concurrency_clone
+
+                    // Extract parameter from the event value as it is delivered
+                    #payload_extraction
+                    #order_slot
+
                     Box::pin(async move {
+                        #pause_check
 
-                        // Extract parameter from the event value
-                        let #param_ident = match value {
-                            Some(value) => match value.clone().as_type::<#param_type>() {
-                                Ok(val) => val,
-                                Err(err) => {
-                                    return Err(anyhow!(format!("Failed to parse event value as {}: {}", stringify!(#param_type), err)));
-                                }
-                            },
-                            None => {
-                                return Err(anyhow!(format!("Required event value is missing for {}", #path_value)));
-                            }
+                        let #param_ident = match payload {
+                            Ok(payload) => payload,
+                            Err(err) => return Err(err),
                         };
 
+                        #meta_extractions
+
+                        #dedup_check
+
+                        #order_acquire
+                        #concurrency_acquire
+
                         // Call the handler method with the extracted parameter
-                        match self_clone.#fn_ident(#param_ident, &ctx).await {
-                            Ok(_) => Ok(()),
+                        match #handler_call {
+                            #handler_ok
                             Err(err) => {
-                                Err(anyhow!(format!("Error in event handler for {}: {}", #path_value, err)))
+                                Err(anyhow::anyhow!(format!("Error in event handler for {}: {}", #path_value, err)))
                             }
                         }
                     })
@@ -125,21 +797,38 @@ pub fn subscribe_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
             }
         }
     } else if params.is_empty() {
+        // Errors are logged by the error branch, which covers a caught panic too
+        let handler_call = guarded_call(false);
         quote! {
-            async fn #register_method_name(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+            #[doc(hidden)]
+            async fn #register_method_name(&self, context: &runar_node::services::LifecycleContext) -> anyhow::Result<()>
+            where
+                // A service that isn't thread-safe is only reported by the service's Send + Sync assertion
+                for<'__service> Self: Send + Sync,
+            {
                 context.info(format!("Subscribing to '{}' event", #path_value));
+                #pause_reset
 
                 // Create a clone of self that can be moved into the closure
                 let self_clone = self.clone();
 
+                #concurrency_setup
+
                 // Register the event handler
-                context.subscribe(#path, Box::new(move |ctx, value| {
+                context.subscribe(#subscription_path, Box::new(move |ctx, value| {
                     // Create a boxed future that returns Result<(), anyhow::Error>
                     let self_clone = self_clone.clone();
+                    #concurrency_clone
                     Box::pin(async move {
+                        #pause_check
+
+                        #meta_extractions
+
+                        #concurrency_acquire
+
                         // Call the handler method directly with the event context
-                        match self_clone.#fn_ident(&ctx).await {
-                            Ok(_) => Ok(()),
+                        match #handler_call {
+                            #handler_ok
                             Err(err) => {
                                 ctx.error(format!("Error in event handler for {}: {}", #path_value, err));
                                 Ok(()) // Still return Ok to prevent subscription cancellation
@@ -154,19 +843,39 @@ pub fn subscribe_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     } else {
         // Multiple parameters case - this is not supported for subscriptions
+        let param_names = params
+            .iter()
+            .map(|(param_ident, _)| format!("`{}`", param_ident))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let error = syn::Error::new_spanned(
+            &input.sig,
+            format!(
+                "Subscription handlers can only have one payload parameter plus context, found {}: {}",
+                params.len(),
+                param_names
+            ),
+        )
+        .to_compile_error();
+
+        // Keep the registration method so the service macro's call to it doesn't cascade errors
         quote! {
-            compile_error!("Subscription handlers can only have one parameter plus context");
+            #[doc(hidden)]
+            async fn #register_method_name(&self, _context: &runar_node::services::LifecycleContext) -> anyhow::Result<()> {
+                #error
+            }
         }
     };
 
     // Combine the original function with the generated register method
     let expanded = quote! {
-        // Keep the original function
-        #(#attrs)*
-        #vis #input
+        // Keep the original function, it already carries its attributes and visibility
+        #input
 
         // Add the registration method
         #register_method
+
+        #pause_methods
     };
 
     TokenStream::from(expanded)
@@ -178,14 +887,18 @@ fn extract_parameters(input: &ItemFn) -> Vec<(Ident, Type)> {
 
     for arg in &input.sig.inputs {
         match arg {
-            FnArg::Typed(PatType { pat, ty, .. }) => {
+            FnArg::Typed(PatType { pat, ty, attrs, .. }) => {
+                // Parameters annotated with #[from_event_meta] are read from the event context
+                if attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("from_event_meta"))
+                {
+                    continue;
+                }
+
                 // Skip the self parameter and context parameter
                 if let Pat::Ident(PatIdent { ident, .. }) = &**pat {
-                    let ident_string = ident.to_string();
-                    if ident_string != "self"
-                        && ident_string != "ctx"
-                        && !ident_string.ends_with("ctx")
-                    {
+                    if ident != "self" && !is_context_parameter(ident, ty, attrs) {
                         params.push((ident.clone(), (**ty).clone()));
                     }
                 }
@@ -196,3 +909,154 @@ fn extract_parameters(input: &ItemFn) -> Vec<(Ident, Type)> {
 
     params
 }
+
+/// Check whether a path segment is a `{name}` placeholder
+fn is_placeholder(segment: &str) -> bool {
+    segment.len() > 2 && segment.starts_with('{') && segment.ends_with('}')
+}
+
+/// Find the `{name}` placeholders of a subscription path with their segment index
+fn path_captures(path: &str) -> Vec<(usize, String)> {
+    path.split('/')
+        .enumerate()
+        .filter(|(_, segment)| is_placeholder(segment))
+        .map(|(index, segment)| (index, segment[1..segment.len() - 1].to_string()))
+        .collect()
+}
+
+/// Extract parameters annotated with #[from_event_meta("key")] along with their metadata key
+fn extract_event_meta_parameters(input: &ItemFn) -> syn::Result<Vec<(Ident, Type, LitStr)>> {
+    let mut params = Vec::new();
+
+    for arg in &input.sig.inputs {
+        if let FnArg::Typed(PatType { pat, ty, attrs, .. }) = arg {
+            if let (Some(key), Pat::Ident(PatIdent { ident, .. })) =
+                (find_event_meta_key(attrs)?, &**pat)
+            {
+                params.push((ident.clone(), (**ty).clone(), key));
+            }
+        }
+    }
+
+    Ok(params)
+}
+
+/// Find the metadata key of a #[from_event_meta("key")] parameter attribute
+fn find_event_meta_key(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    let Some(attr) = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("from_event_meta"))
+    else {
+        return Ok(None);
+    };
+    attr.parse_args::<LitStr>().map(Some).map_err(|_| {
+        syn::Error::new_spanned(
+            attr,
+            "Expected a metadata key string, e.g. #[from_event_meta(\"timestamp\")]",
+        )
+    })
+}
+
+/// Remove the #[from_event_meta] markers so the emitted handler signature stays valid
+fn strip_event_meta_attributes(input: &mut ItemFn) {
+    for arg in input.sig.inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = arg {
+            pat_type
+                .attrs
+                .retain(|attr| !attr.path().is_ident("from_event_meta"));
+        }
+    }
+}
+
+/// Generate the handler call arguments in declaration order
+fn generate_call_arguments(input: &ItemFn) -> Vec<TokenStream2> {
+    input
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(PatType { pat, ty, attrs, .. }) => match &**pat {
+                Pat::Ident(PatIdent { ident, .. }) => {
+                    if is_context_parameter(ident, ty, attrs) {
+                        Some(quote! { &ctx })
+                    } else if quote! { #ty }.to_string() == "& str" {
+                        // &str payloads are extracted as an owned String
+                        Some(quote! { &#ident })
+                    } else {
+                        Some(quote! { #ident })
+                    }
+                }
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// Check if a parameter is the handler's context, by name (`ctx`, `*ctx`) or by a `*Context` type
+///
+/// Parameters marked with `#[param]` are always data.
+fn is_context_parameter(ident: &Ident, ty: &Type, attrs: &[Attribute]) -> bool {
+    if has_marker(attrs, "param") {
+        return false;
+    }
+
+    let ident_string = ident.to_string();
+    if ident_string == "ctx" || ident_string.ends_with("ctx") {
+        return true;
+    }
+
+    let ty = match ty {
+        Type::Reference(type_ref) => &*type_ref.elem,
+        other => other,
+    };
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|seg| seg.ident.to_string().ends_with("Context")),
+        _ => false,
+    }
+}
+
+/// Ensure the context parameter, if any, is declared as `&EventContext`
+fn validate_context_parameter(input: &ItemFn) -> Result<()> {
+    for arg in &input.sig.inputs {
+        let FnArg::Typed(PatType { pat, ty, attrs, .. }) = arg else {
+            continue;
+        };
+        let Pat::Ident(PatIdent { ident, .. }) = &**pat else {
+            continue;
+        };
+        if !is_context_parameter(ident, ty, attrs) {
+            continue;
+        }
+
+        let is_event_context = match &**ty {
+            Type::Reference(type_ref) => match &*type_ref.elem {
+                Type::Path(type_path) => type_path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|seg| seg.ident == "EventContext"),
+                _ => false,
+            },
+            _ => false,
+        };
+        if !is_event_context {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!(
+                    "Subscription handlers are called with the event context, expected `&EventContext` but found `{}`",
+                    quote! { #ty }
+                        .to_string()
+                        .replace(" :: ", "::")
+                        .replace("& ", "&")
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+// Test node macro implementation
+//
+// This module implements the test_node macro, which builds a node for
+// integration tests with networking disabled, adds the given services
+// and starts it.
+
+use proc_macro::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{parse::Parser, punctuated::Punctuated, spanned::Spanned, Expr, Token};
+
+/// Implementation of the test_node macro
+pub fn test_node_macro(input: TokenStream) -> TokenStream {
+    let services = match Punctuated::<Expr, Token![,]>::parse_terminated.parse(input) {
+        Ok(services) => services,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Each service is added with its own span, so a type that isn't a service is
+    // reported on its expression
+    let additions = services.iter().map(|service| {
+        quote_spanned! {service.span()=>
+            node.add_service(#service)
+                .await
+                .expect(concat!("Failed to add service ", stringify!(#service)));
+        }
+    });
+
+    let expanded = quote! {
+        {
+            let logging_config = runar_node::config::LoggingConfig::new()
+                .with_default_level(runar_node::config::LogLevel::Debug);
+            let mut config = runar_node::NodeConfig::new("test-node", "test_network")
+                .with_logging_config(logging_config);
+            // Disable networking
+            config.network_config = None;
+            let mut node = runar_node::Node::new(config)
+                .await
+                .expect("Failed to create the test node");
+            #(#additions)*
+            node.start().await.expect("Failed to start the test node");
+            node
+        }
+    };
+
+    TokenStream::from(expanded)
+}
@@ -5,7 +5,7 @@
 
 use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
-use syn::{Attribute, Lit, Meta, Path};
+use syn::{Attribute, Expr, ExprLit, Lit, LitBool, LitInt, LitStr, Meta, Path};
 
 /// Extract a string value from an attribute
 pub fn extract_string_from_attribute(
@@ -40,6 +40,182 @@ pub fn path_matches(path: &Path, segments: &[&str]) -> bool {
         .all(|(seg, &expected)| seg.ident == expected)
 }
 
+/// Check if an attribute is one of this crate's macros, written bare (`#[action]`)
+/// or fully qualified (`#[runar_macros::action]`)
+pub fn is_macro_attribute(attr: &Attribute, name: &str) -> bool {
+    attr.path().is_ident(name) || path_matches(attr.path(), &["runar_macros", name])
+}
+
+/// Extract a string literal from an option value
+pub fn expect_lit_str(expr: &Expr) -> syn::Result<LitStr> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) => Ok(lit_str.clone()),
+        _ => Err(syn::Error::new_spanned(expr, "Expected a string literal")),
+    }
+}
+
+/// Read the boolean literal value of a flag option, `option` names it in the error
+pub fn expect_lit_bool(expr: &Expr, option: &str) -> syn::Result<LitBool> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Bool(lit_bool),
+            ..
+        }) => Ok(lit_bool.clone()),
+        _ => Err(syn::Error::new_spanned(
+            expr,
+            format!("Expected a boolean literal for {}", option),
+        )),
+    }
+}
+
+/// Read the integer literal value of a size or limit option, which must be positive and fit in `N`
+pub fn expect_positive_int<N>(expr: &Expr) -> syn::Result<LitInt>
+where
+    N: std::str::FromStr + Default + PartialOrd,
+    N::Err: std::fmt::Display,
+{
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit_int),
+            ..
+        }) if lit_int.base10_parse::<N>()? > N::default() => Ok(lit_int.clone()),
+        _ => Err(syn::Error::new_spanned(
+            expr,
+            "Expected a positive integer literal",
+        )),
+    }
+}
+
+/// Check if a type string (as produced by `quote!`) is a `Cow<str>`
+pub fn is_cow_str_type(type_str: &str) -> bool {
+    let compact: String = type_str.chars().filter(|c| !c.is_whitespace()).collect();
+    let last_segment = compact.rsplit("::").next().unwrap_or(&compact);
+    last_segment.starts_with("Cow<") && last_segment.ends_with("str>")
+}
+
+/// Check if a type string (as produced by `quote!`) is a binary payload, `Vec<u8>` or `Bytes`
+pub fn is_bytes_type(type_str: &str) -> bool {
+    let compact: String = type_str.chars().filter(|c| !c.is_whitespace()).collect();
+    let last_segment = compact.rsplit("::").next().unwrap_or(&compact);
+    last_segment == "Vec<u8>" || last_segment == "Bytes"
+}
+
+/// Check if a parameter type (as produced by `quote!`) is a primitive read directly from the map
+pub fn is_primitive_param_type(type_str: &str) -> bool {
+    matches!(
+        type_str,
+        "i32" | "i64" | "u32" | "u64" | "f32" | "f64" | "bool" | "String" | "& str"
+    ) || is_cow_str_type(type_str)
+}
+
+/// Markers an action parameter can carry. `#[param]` makes it data regardless of its name,
+/// `#[request_id]` and `#[cancel]` read it from the context, `#[file]` from a binary part of
+/// the request, `#[from_path_or_payload]` from the path captures before the payload, and
+/// `#[alias(...)]` lists its former names.
+pub const PARAMETER_MARKERS: &[&str] = &[
+    "param",
+    "request_id",
+    "cancel",
+    "file",
+    "from_path_or_payload",
+    "alias",
+];
+
+/// Check if a parameter carries the `#[<name>]` marker
+pub fn has_marker(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+/// Remove the named markers from the parameters so the emitted signature stays valid
+pub fn strip_markers(sig: &mut syn::Signature, names: &[&str]) {
+    for arg in sig.inputs.iter_mut() {
+        if let syn::FnArg::Typed(pat_type) = arg {
+            pat_type
+                .attrs
+                .retain(|attr| !names.iter().any(|name| attr.path().is_ident(name)));
+        }
+    }
+}
+
+/// Get the former names given to a parameter with `#[alias("old_name", ...)]`
+pub fn parameter_aliases(attrs: &[Attribute]) -> syn::Result<Vec<syn::LitStr>> {
+    let mut aliases = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("alias")) {
+        let names = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated,
+        )?;
+        if names.is_empty() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "Expected at least one former parameter name, e.g. #[alias(\"old_name\")]",
+            ));
+        }
+        aliases.extend(names);
+    }
+    Ok(aliases)
+}
+
+/// Extract the `T` of a `Result<T>` return type
+pub fn extract_result_ok_type(return_type: &syn::ReturnType) -> Option<syn::Type> {
+    let syn::ReturnType::Type(_, ty) = return_type else {
+        return None;
+    };
+    let syn::Type::Path(type_path) = &**ty else {
+        return None;
+    };
+    let seg = type_path.path.segments.last()?;
+    if seg.ident != "Result" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(ref ab) = seg.arguments else {
+        return None;
+    };
+    ab.args.iter().find_map(|arg| match arg {
+        syn::GenericArgument::Type(inner_ty) => Some(inner_ty.clone()),
+        _ => None,
+    })
+}
+
+/// Get the element type `T` of a `Vec<T>` type
+pub fn vec_element_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let seg = type_path.path.segments.last()?;
+    if seg.ident != "Vec" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(ref ab) = seg.arguments else {
+        return None;
+    };
+    ab.args.iter().find_map(|arg| match arg {
+        syn::GenericArgument::Type(inner_ty) => Some(inner_ty),
+        _ => None,
+    })
+}
+
+/// Get the key and value types `K` and `V` of a `HashMap<K, V>` or `BTreeMap<K, V>` type
+pub fn map_key_value_types(ty: &syn::Type) -> Option<(&syn::Type, &syn::Type)> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let seg = type_path.path.segments.last()?;
+    if seg.ident != "HashMap" && seg.ident != "BTreeMap" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(ref ab) = seg.arguments else {
+        return None;
+    };
+    let mut types = ab.args.iter().filter_map(|arg| match arg {
+        syn::GenericArgument::Type(inner_ty) => Some(inner_ty),
+        _ => None,
+    });
+    Some((types.next()?, types.next()?))
+}
+
 /// Generate a handler function name from an action name
 pub fn generate_handler_name(action_name: &str) -> syn::Ident {
     syn::Ident::new(&format!("handle_{}", action_name), Span::call_site())
@@ -68,12 +244,12 @@ pub fn generate_param_extraction(
                 Ok(val) => val,
                 Err(_) => {
                     context.error(format!("Failed to parse parameter {} for action {}", #index, #action_name));
-                    return Err(anyhow!(format!("Invalid parameter type for parameter {}", #index)));
+                    return Err(anyhow::anyhow!(format!("Invalid parameter type for parameter {}", #index)));
                 }
             },
             None => {
                 context.error(format!("Missing parameter {} for action {}", #index, #action_name));
-                return Err(anyhow!(format!("Missing parameter {} for action {}", #index, #action_name)));
+                return Err(anyhow::anyhow!(format!("Missing parameter {} for action {}", #index, #action_name)));
             }
         };
     }
@@ -0,0 +1,161 @@
+// Value map macro implementation
+//
+// This module implements the vmap_arc macro, which builds an ArcValueType map
+// from `key => value` pairs, converting primitives and nesting maps, and the
+// varr_arc macro, which builds an ArcValueType list from its elements.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    braced, bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    token::{Brace, Bracket},
+    Expr, ExprLit, Lit, Result, Token,
+};
+
+// Define a struct to parse the `key => value` entries of a map
+pub struct VmapImpl {
+    pub entries: Punctuated<VmapEntry, Token![,]>,
+}
+
+/// A single `key => value` entry
+pub struct VmapEntry {
+    pub key: Expr,
+    pub value: VmapValue,
+}
+
+// Define a struct to parse the elements of a list
+pub struct VarrImpl {
+    pub elements: Punctuated<VmapValue, Token![,]>,
+}
+
+/// The value of an entry or list element, either a nested map, a nested list
+/// or a primitive expression
+pub enum VmapValue {
+    Map(VmapImpl),
+    List(VarrImpl),
+    Primitive(Expr),
+}
+
+impl Parse for VmapImpl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(VmapImpl {
+            entries: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+impl Parse for VmapEntry {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let key = input.parse::<Expr>()?;
+        input.parse::<Token![=>]>()?;
+
+        // Values are parsed like list elements, any other expression
+        // (including plain blocks) is a primitive value
+        let value = parse_element(input)?;
+
+        Ok(VmapEntry { key, value })
+    }
+}
+
+impl Parse for VarrImpl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(VarrImpl {
+            elements: Punctuated::parse_terminated_with(input, parse_element)?,
+        })
+    }
+}
+
+/// Parse a list element, braced `key => value` pairs are a nested map and a
+/// bracketed group is a nested list
+fn parse_element(input: ParseStream) -> Result<VmapValue> {
+    if input.peek(Brace) && is_nested_map(input) {
+        let content;
+        braced!(content in input);
+        Ok(VmapValue::Map(content.parse::<VmapImpl>()?))
+    } else if input.peek(Bracket) {
+        let content;
+        bracketed!(content in input);
+        Ok(VmapValue::List(content.parse::<VarrImpl>()?))
+    } else {
+        Ok(VmapValue::Primitive(input.parse::<Expr>()?))
+    }
+}
+
+/// Check whether the braced group at the cursor contains `key => value` pairs
+fn is_nested_map(input: ParseStream) -> bool {
+    fn parse_braced_map(input: ParseStream) -> Result<VmapImpl> {
+        let content;
+        braced!(content in input);
+        content.parse::<VmapImpl>()
+    }
+
+    parse_braced_map(&input.fork()).is_ok()
+}
+
+/// Implementation of the vmap_arc macro
+pub fn vmap_arc_macro(input: TokenStream) -> TokenStream {
+    let vmap = parse_macro_input!(input as VmapImpl);
+    TokenStream::from(generate_map(&vmap))
+}
+
+/// Implementation of the varr_arc macro
+pub fn varr_arc_macro(input: TokenStream) -> TokenStream {
+    let varr = parse_macro_input!(input as VarrImpl);
+    TokenStream::from(generate_list(&varr))
+}
+
+/// Generate the code building an ArcValueType map from the entries
+fn generate_map(vmap: &VmapImpl) -> TokenStream2 {
+    // The map binding is hidden from the caller's expressions, so a caller
+    // variable named `map` used in a key or value is left alone
+    let map = Ident::new("map", Span::mixed_site());
+    let inserts = vmap.entries.iter().map(|entry| {
+        let key = &entry.key;
+        let value = generate_value(&entry.value);
+        quote! {
+            #map.insert((#key).to_string(), #value);
+        }
+    });
+
+    quote! {
+        {
+            let mut #map = std::collections::HashMap::<String, runar_common::types::ArcValueType>::new();
+            #(#inserts)*
+            runar_common::types::ArcValueType::new_map(#map)
+        }
+    }
+}
+
+/// Generate the code building an ArcValueType list from the elements
+fn generate_list(varr: &VarrImpl) -> TokenStream2 {
+    let values = varr.elements.iter().map(generate_value);
+
+    quote! {
+        {
+            let list: Vec<runar_common::types::ArcValueType> = vec![#(#values),*];
+            runar_common::types::ArcValueType::new_list(list)
+        }
+    }
+}
+
+/// Generate the code converting an entry value into an ArcValueType
+fn generate_value(value: &VmapValue) -> TokenStream2 {
+    match value {
+        VmapValue::Map(nested) => generate_map(nested),
+        VmapValue::List(nested) => generate_list(nested),
+        // String literals are stored as owned Strings
+        VmapValue::Primitive(Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        })) => quote! {
+            runar_common::types::ArcValueType::new_primitive(#lit_str.to_string())
+        },
+        VmapValue::Primitive(expr) => quote! {
+            runar_common::types::ArcValueType::new_primitive(#expr)
+        },
+    }
+}
@@ -0,0 +1,47 @@
+// Test for the action_defaults service attribute
+//
+// Every action inherits the service-wide options unless it sets them
+// itself; meta annotations are merged per key.
+
+use anyhow::Result;
+use runar_macros::{action, service};
+use runar_node::services::RequestContext;
+
+#[derive(Clone)]
+pub struct ReportService;
+
+#[service(
+    name = "Reports",
+    path = "reports",
+    action_defaults(meta(owner = "reports-team", tier = "internal"), log_args = false)
+)]
+impl ReportService {
+    #[action]
+    async fn summary(&self, account: String, ctx: &RequestContext) -> Result<String> {
+        ctx.debug("Building a summary".to_string());
+        Ok(format!("summary of {}", account))
+    }
+
+    // Overrides one annotation and the log_args default
+    #[action(meta(tier = "public"), log_args = true)]
+    async fn export(&self, account: String, ctx: &RequestContext) -> Result<String> {
+        ctx.debug(format!("Exporting {}", account));
+        Ok(format!("export of {}", account))
+    }
+}
+
+#[test]
+fn test_action_defaults() {
+    // Actions inherit the service-wide annotations and options
+    let annotations = ReportService::action_annotations();
+    let summary = &annotations["summary"];
+    assert_eq!(summary.get("owner").unwrap(), "reports-team");
+    assert_eq!(summary.get("tier").unwrap(), "internal");
+    assert_eq!(summary.get("log_args").unwrap(), "false");
+
+    // Per-action options override the defaults
+    let export = &annotations["export"];
+    assert_eq!(export.get("owner").unwrap(), "reports-team");
+    assert_eq!(export.get("tier").unwrap(), "public");
+    assert!(!export.contains_key("log_args"));
+}
@@ -0,0 +1,29 @@
+// Compile-fail tests for macro attribute errors
+//
+// Each file in tests/ui must fail to compile with the error recorded in the
+// matching .stderr file. Files are grouped by the macro they exercise, set
+// TRYBUILD=overwrite to regenerate the expected output.
+
+#[test]
+fn publish_attribute_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/publish_*.rs");
+}
+
+#[test]
+fn subscribe_attribute_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/subscribe_*.rs");
+}
+
+#[test]
+fn action_attribute_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/action_*.rs");
+}
+
+#[test]
+fn service_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/service_*.rs");
+}
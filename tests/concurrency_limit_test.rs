@@ -0,0 +1,64 @@
+// Test that waiting for a max_concurrency permit counts against timeout_ms
+//
+// The permit is held until the response is built, including the `after`
+// hook, which the timeout doesn't cover. A request queued behind one whose
+// hook runs long times out instead of waiting for the permit.
+
+use anyhow::Result;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, service, test_node};
+use runar_node::services::RequestContext;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct WorkerService;
+
+#[service(name = "Worker", path = "worker")]
+impl WorkerService {
+    #[action(max_concurrency = 1, timeout_ms = 100, after = "settle")]
+    async fn work(&self, settle_ms: u64, ctx: &RequestContext) -> Result<u64> {
+        ctx.debug(format!("Working, then settling for {} ms", settle_ms));
+        Ok(settle_ms)
+    }
+
+    // Runs with the permit held, outside of the timeout
+    async fn settle(&self, settle_ms: u64, ctx: &RequestContext) -> Result<u64> {
+        ctx.debug(format!("Settling for {} ms", settle_ms));
+        tokio::time::sleep(Duration::from_millis(settle_ms)).await;
+        Ok(settle_ms)
+    }
+}
+
+#[tokio::test]
+async fn test_permit_wait_times_out() {
+    let node = test_node!(WorkerService);
+
+    // Released before the timeout, the permit goes to the queued request
+    let (first, queued) = tokio::join!(
+        node.request("worker/work", Some(ArcValueType::new_primitive(50u64))),
+        async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            node.request("worker/work", Some(ArcValueType::new_primitive(0u64)))
+                .await
+        }
+    );
+    assert_eq!(first.unwrap().unwrap().as_type::<u64>().unwrap(), 50);
+    assert_eq!(queued.unwrap().unwrap().as_type::<u64>().unwrap(), 0);
+
+    // Held past the timeout, the queued request gives up waiting for it
+    let (first, queued) = tokio::join!(
+        node.request("worker/work", Some(ArcValueType::new_primitive(300u64))),
+        async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            node.request("worker/work", Some(ArcValueType::new_primitive(0u64)))
+                .await
+        }
+    );
+    assert_eq!(first.unwrap().unwrap().as_type::<u64>().unwrap(), 300);
+    let err = queued.unwrap_err().to_string();
+    assert!(
+        err.contains("timed out after 100 ms waiting for a permit"),
+        "{}",
+        err
+    );
+}
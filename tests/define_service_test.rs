@@ -0,0 +1,69 @@
+// Test for the define_service macro
+//
+// The declarative form must expand into the same service as a struct with a
+// #[service] impl block of #[action] methods.
+
+use anyhow::Result;
+use runar_common::types::ArcValueType;
+use runar_macros::define_service;
+use runar_node::services::abstract_service::AbstractService;
+use runar_node::services::RequestContext;
+use std::collections::HashMap;
+
+define_service! {
+    service: pub CalculatorService,
+    name: "Calculator Service",
+    path: "calculator",
+    description: "Declarative calculator",
+    version: "0.1.0",
+    actions: [
+        async fn add(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
+            ctx.debug(format!("Adding {} + {}", a, b));
+            Ok(a + b)
+        }
+
+        #[runar_macros::action(path = "times")]
+        async fn multiply(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
+            ctx.debug(format!("Multiplying {} * {}", a, b));
+            Ok(a * b)
+        }
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_node::Node;
+    use runar_node::NodeConfig;
+
+    #[test]
+    fn test_declared_service_metadata() {
+        let service = CalculatorService;
+        assert_eq!(service.name(), "Calculator Service");
+        assert_eq!(service.path(), "calculator");
+        assert_eq!(service.version(), "0.1.0");
+
+        let paths = CalculatorService::all_action_paths();
+        assert!(paths.contains(&"add"));
+        assert!(paths.contains(&"times"));
+    }
+
+    #[tokio::test]
+    async fn test_declared_service_actions() {
+        let mut config = NodeConfig::new("test-node", "test_network");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        node.add_service(CalculatorService).await.unwrap();
+        node.start().await.unwrap();
+
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), 6.0);
+        map.insert("b".to_string(), 7.0);
+        let response = node
+            .request("calculator/times", Some(ArcValueType::new_map(map)))
+            .await
+            .unwrap();
+        assert_eq!(response.unwrap().as_type::<f64>().unwrap(), 42.0);
+    }
+}
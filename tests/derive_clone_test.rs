@@ -0,0 +1,59 @@
+// Test for the derive_clone option of #[service] on the struct
+//
+// The generated Clone impl clones each field, so a clone shares the Arc'd
+// state with the original and copies the plain fields.
+
+use anyhow::Result;
+use futures::lock::Mutex;
+use runar_macros::{action, service, test_node};
+use runar_node::services::RequestContext;
+use std::sync::Arc;
+
+#[service(derive_clone = true)]
+pub struct CounterService {
+    counts: Arc<Mutex<Vec<u32>>>,
+    step: u32,
+}
+
+#[service(name = "Counter", path = "counter")]
+impl CounterService {
+    #[action]
+    async fn bump(&self, ctx: &RequestContext) -> Result<u32> {
+        let mut counts = self.counts.lock().await;
+        let next = counts.last().copied().unwrap_or(0) + self.step;
+        ctx.debug(format!("Bumping to {}", next));
+        counts.push(next);
+        Ok(next)
+    }
+}
+
+#[service(derive_clone = true)]
+pub struct Pair<T>(T, String);
+
+#[test]
+fn test_clone_fields() {
+    let service = CounterService {
+        counts: Arc::new(Mutex::new(Vec::new())),
+        step: 5,
+    };
+    let clone = service.clone();
+    assert!(Arc::ptr_eq(&service.counts, &clone.counts));
+    assert_eq!(clone.step, 5);
+
+    let pair = Pair(3u8, "three".to_string());
+    let clone = pair.clone();
+    assert_eq!((clone.0, clone.1), (3, "three".to_string()));
+}
+
+#[tokio::test]
+async fn test_cloned_service_serves_requests() {
+    let counts = Arc::new(Mutex::new(Vec::new()));
+    let node = test_node!(CounterService {
+        counts: counts.clone(),
+        step: 2,
+    });
+
+    let response = node.request("counter/bump", None).await.unwrap();
+    assert_eq!(response.unwrap().as_type::<u32>().unwrap(), 2);
+    assert_eq!(*counts.lock().await, vec![2]);
+}
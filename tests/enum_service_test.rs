@@ -0,0 +1,68 @@
+// Test for #[service] on an enum
+//
+// The enum wraps one of two backends and delegates the AbstractService
+// impl to whichever variant is active, so the implementation can be
+// picked at runtime.
+
+use anyhow::Result;
+use runar_macros::{action, service};
+use runar_node::services::abstract_service::AbstractService;
+use runar_node::services::RequestContext;
+
+mod memory {
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct MemoryStore;
+
+    #[service(name = "Memory Store", path = "memory_store", version = "1.0.0")]
+    impl MemoryStore {
+        #[action]
+        async fn get(&self, key: String, ctx: &RequestContext) -> Result<String> {
+            ctx.debug(format!("Reading {} from memory", key));
+            Ok(format!("memory:{}", key))
+        }
+    }
+}
+
+mod disk {
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct DiskStore;
+
+    #[service(name = "Disk Store", path = "disk_store", version = "2.0.0")]
+    impl DiskStore {
+        #[action]
+        async fn get(&self, key: String, ctx: &RequestContext) -> Result<String> {
+            ctx.debug(format!("Reading {} from disk", key));
+            Ok(format!("disk:{}", key))
+        }
+    }
+}
+
+// The service is chosen when the node is configured
+#[service]
+pub enum Store {
+    Memory(memory::MemoryStore),
+    Disk(disk::DiskStore),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enum_service_delegates_to_the_active_variant() {
+        let store = Store::Memory(memory::MemoryStore);
+        assert_eq!(store.name(), "Memory Store");
+        assert_eq!(store.path(), "memory_store");
+        assert_eq!(store.version(), "1.0.0");
+
+        let store = Store::Disk(disk::DiskStore);
+        assert_eq!(store.name(), "Disk Store");
+        assert_eq!(store.path(), "disk_store");
+        assert_eq!(store.version(), "2.0.0");
+        assert_eq!(store.network_id(), None);
+    }
+}
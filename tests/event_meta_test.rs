@@ -0,0 +1,63 @@
+// Test for #[from_event_meta] subscription parameters
+//
+// Events published with ctx.publish carry no "source" metadata entry, so
+// the handler reading it rejects the event instead of running with a
+// made-up value, while a handler without the parameter still gets it.
+
+use anyhow::Result;
+use futures::lock::Mutex;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, service, subscribe, test_node};
+use runar_node::services::{EventContext, RequestContext};
+use std::{collections::HashMap, sync::Arc};
+
+#[derive(Clone)]
+pub struct SensorService {
+    seen: Arc<Mutex<HashMap<String, String>>>,
+}
+
+#[service(name = "Sensors", path = "sensors")]
+impl SensorService {
+    #[action]
+    async fn record(&self, reading: f64, ctx: &RequestContext) -> Result<()> {
+        ctx.publish("readings", Some(ArcValueType::new_primitive(reading)))
+            .await
+    }
+
+    #[subscribe(path = "sensors/readings")]
+    async fn on_reading(&self, reading: f64, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("Reading {}", reading));
+        self.seen
+            .lock()
+            .await
+            .insert("plain".to_string(), reading.to_string());
+        Ok(())
+    }
+
+    #[subscribe(path = "sensors/readings")]
+    async fn on_sourced_reading(
+        &self,
+        reading: f64,
+        #[from_event_meta("source")] source: String,
+        ctx: &EventContext,
+    ) -> Result<()> {
+        ctx.debug(format!("Reading {} from {}", reading, source));
+        self.seen.lock().await.insert("sourced".to_string(), source);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_missing_event_metadata() {
+    let seen = Arc::new(Mutex::new(HashMap::new()));
+    let node = test_node!(SensorService { seen: seen.clone() });
+
+    node.request("sensors/record", Some(ArcValueType::new_primitive(21.5)))
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let seen = seen.lock().await;
+    assert_eq!(seen.get("plain").unwrap(), "21.5");
+    assert!(!seen.contains_key("sourced"));
+}
@@ -0,0 +1,44 @@
+// Test for the expose option of #[service] on the struct
+//
+// Exposed fields get a `pub fn` accessor returning a reference, the fields
+// themselves stay private.
+
+use anyhow::Result;
+use futures::lock::Mutex;
+use runar_macros::{action, service};
+use runar_node::services::RequestContext;
+use std::{collections::HashMap, sync::Arc};
+
+mod session {
+    use super::*;
+
+    #[service(expose = ["sessions", "ttl_secs"])]
+    #[derive(Clone)]
+    pub struct SessionService {
+        sessions: Arc<Mutex<HashMap<String, String>>>,
+        ttl_secs: u64,
+    }
+
+    #[service(name = "Sessions", path = "sessions")]
+    impl SessionService {
+        pub fn new(sessions: Arc<Mutex<HashMap<String, String>>>, ttl_secs: u64) -> Self {
+            Self { sessions, ttl_secs }
+        }
+
+        #[action]
+        async fn lookup(&self, token: String, ctx: &RequestContext) -> Result<Option<String>> {
+            ctx.debug(format!("Looking up session {}", token));
+            Ok(self.sessions.lock().await.get(&token).cloned())
+        }
+    }
+}
+
+use session::SessionService;
+
+#[test]
+fn test_exposed_field_accessors() {
+    let sessions = Arc::new(Mutex::new(HashMap::new()));
+    let service = SessionService::new(sessions.clone(), 3600);
+    assert!(Arc::ptr_eq(service.sessions(), &sessions));
+    assert_eq!(*service.ttl_secs(), 3600);
+}
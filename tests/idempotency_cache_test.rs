@@ -0,0 +1,56 @@
+// Test for the idempotency cache eviction order
+//
+// The cache holds two keys. A cache hit makes its key the most recently
+// used, so the next new key evicts the other one.
+
+use anyhow::Result;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, service, test_node};
+use runar_node::services::RequestContext;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct PaymentService {
+    executions: Arc<AtomicU32>,
+}
+
+#[service(name = "Payments", path = "payments")]
+impl PaymentService {
+    #[action(idempotency_key = "request_id", idempotency_cache_size = 2)]
+    async fn charge(&self, request_id: String, amount: f64, ctx: &RequestContext) -> Result<u32> {
+        ctx.debug(format!("Charging {} for {}", amount, request_id));
+        Ok(self.executions.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+}
+
+#[tokio::test]
+async fn test_cache_hit_refreshes_key() {
+    let executions = Arc::new(AtomicU32::new(0));
+    let node = test_node!(PaymentService {
+        executions: executions.clone()
+    });
+    let charge = |request_id: &str| {
+        let mut map = HashMap::new();
+        map.insert(
+            "request_id".to_string(),
+            ArcValueType::new_primitive(request_id.to_string()),
+        );
+        map.insert("amount".to_string(), ArcValueType::new_primitive(9.5));
+        node.request("payments/charge", Some(ArcValueType::new_map(map)))
+    };
+
+    charge("a").await.unwrap();
+    charge("b").await.unwrap();
+    // A hit on "a" leaves "b" as the least recently used key
+    charge("a").await.unwrap();
+    charge("c").await.unwrap();
+    assert_eq!(executions.load(Ordering::SeqCst), 3);
+
+    // "a" is still cached, "b" was evicted and runs again
+    charge("a").await.unwrap();
+    assert_eq!(executions.load(Ordering::SeqCst), 3);
+    charge("b").await.unwrap();
+    assert_eq!(executions.load(Ordering::SeqCst), 4);
+}
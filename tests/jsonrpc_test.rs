@@ -0,0 +1,129 @@
+// Test for the jsonrpc service attribute
+//
+// A gateway action hands JSON-RPC requests to another service, which runs
+// them through the same handlers as node requests.
+
+use anyhow::Result;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, service, test_node};
+use runar_node::services::RequestContext;
+
+mod calculator {
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct CalculatorService;
+
+    #[service(name = "Calculator", path = "calculator", jsonrpc = true)]
+    impl CalculatorService {
+        #[action]
+        async fn add(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
+            ctx.debug(format!("Adding {} + {}", a, b));
+            Ok(a + b)
+        }
+
+        #[action]
+        async fn divide(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
+            ctx.debug(format!("Dividing {} / {}", a, b));
+            if b == 0.0 {
+                return Err(anyhow::anyhow!("Division by zero"));
+            }
+            Ok(a / b)
+        }
+    }
+}
+
+mod gateway {
+    use super::calculator::CalculatorService;
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct GatewayService {
+        pub calculator: CalculatorService,
+    }
+
+    #[service(name = "Gateway", path = "gateway")]
+    impl GatewayService {
+        #[action]
+        async fn rpc(&self, request: String, ctx: &RequestContext) -> Result<String> {
+            let request = serde_json::from_str(&request)?;
+            Ok(self
+                .calculator
+                .handle_jsonrpc(request, ctx)
+                .await
+                .to_string())
+        }
+    }
+}
+
+use calculator::CalculatorService;
+use gateway::GatewayService;
+
+#[tokio::test]
+async fn test_jsonrpc() {
+    let gateway = GatewayService {
+        calculator: CalculatorService,
+    };
+    let node = test_node!(gateway);
+
+    let call = |request: serde_json::Value| {
+        let node = &node;
+        async move {
+            let params = ArcValueType::new_primitive(request.to_string());
+            let mut response = node
+                .request("gateway/rpc", Some(params))
+                .await
+                .unwrap()
+                .unwrap();
+            serde_json::from_str::<serde_json::Value>(&response.as_type::<String>().unwrap())
+                .unwrap()
+        }
+    };
+
+    // Params by name and by position
+    let response = call(serde_json::json!({
+        "jsonrpc": "2.0", "method": "add", "params": { "a": 2.0, "b": 3.0 }, "id": 1
+    }))
+    .await;
+    assert_eq!(response["result"], 5.0);
+    assert_eq!(response["id"], 1);
+    let response = call(serde_json::json!({
+        "jsonrpc": "2.0", "method": "add", "params": [2.0, 3.0], "id": 2
+    }))
+    .await;
+    assert_eq!(response["result"], 5.0);
+
+    // A primitive of the wrong type is rejected before the handler runs
+    let response = call(serde_json::json!({
+        "jsonrpc": "2.0", "method": "add", "params": { "a": "two", "b": 3.0 }, "id": 3
+    }))
+    .await;
+    assert_eq!(response["error"]["code"], -32602);
+
+    // Errors returned by the handler
+    let response = call(serde_json::json!({
+        "jsonrpc": "2.0", "method": "divide", "params": { "a": 1.0, "b": 0.0 }, "id": 4
+    }))
+    .await;
+    assert_eq!(response["error"]["code"], -32603);
+    assert!(response["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Division by zero"));
+
+    // Unknown methods and malformed requests
+    let response = call(serde_json::json!({
+        "jsonrpc": "2.0", "method": "sqrt", "params": [4.0], "id": 5
+    }))
+    .await;
+    assert_eq!(response["error"]["code"], -32601);
+    let response = call(serde_json::json!({ "method": "add", "id": 6 })).await;
+    assert_eq!(response["error"]["code"], -32600);
+
+    // Notifications get no response
+    let response = call(serde_json::json!({
+        "jsonrpc": "2.0", "method": "add", "params": [1.0, 1.0]
+    }))
+    .await;
+    assert!(response.is_null());
+}
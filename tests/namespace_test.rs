@@ -0,0 +1,113 @@
+// Test for the namespace service attribute
+//
+// Relative #[subscribe] and #[publish] topics are prefixed with the
+// namespace, absolute topics (leading `/`) are used as written.
+
+use anyhow::Result;
+use futures::lock::Mutex;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, publish, service, subscribe};
+use runar_node::services::{EventContext, RequestContext};
+use std::{collections::HashMap, sync::Arc};
+
+#[derive(Clone)]
+pub struct BillingService {
+    store: Arc<Mutex<HashMap<String, ArcValueType>>>,
+}
+
+// Every published topic has a subscriber and vice versa, so check_topics stays quiet
+#[service(
+    name = "Billing",
+    path = "billing_api",
+    namespace = "billing",
+    check_topics = true
+)]
+impl BillingService {
+    // Published to `billing/invoiced`
+    #[publish(path = "invoiced")]
+    #[action]
+    async fn invoice(&self, amount: f64, ctx: &RequestContext) -> Result<f64> {
+        ctx.debug(format!("Invoicing {}", amount));
+        Ok(amount)
+    }
+
+    // Published to `audit/invoiced`, outside of the namespace
+    #[publish(path = "/audit/invoiced")]
+    #[action]
+    async fn audited_invoice(&self, amount: f64, ctx: &RequestContext) -> Result<f64> {
+        ctx.debug(format!("Invoicing {} with an audit trail", amount));
+        Ok(amount)
+    }
+
+    // Subscribed to `billing/invoiced`
+    #[subscribe(path = "invoiced")]
+    async fn on_invoiced(&self, amount: f64, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("invoiced: {}", amount));
+        self.store
+            .lock()
+            .await
+            .insert("invoiced".to_string(), ArcValueType::new_primitive(amount));
+        Ok(())
+    }
+
+    // Subscribed to `audit/invoiced`
+    #[subscribe("/audit/invoiced")]
+    async fn on_audited(&self, amount: f64, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("audited: {}", amount));
+        self.store
+            .lock()
+            .await
+            .insert("audited".to_string(), ArcValueType::new_primitive(amount));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_node::Node;
+    use runar_node::NodeConfig;
+
+    #[test]
+    fn test_events_emitter_uses_namespaced_topics() {
+        // The emitter methods are named after the topics the results are published to
+        let _ = BillingServiceEvents::publish_billing_invoiced;
+        let _ = BillingServiceEvents::publish_audit_invoiced;
+    }
+
+    #[tokio::test]
+    async fn test_namespaced_subscribe_and_publish() {
+        let mut config = NodeConfig::new("test-node", "test_network");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        let store = Arc::new(Mutex::new(HashMap::new()));
+        let service = BillingService {
+            store: store.clone(),
+        };
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        node.request(
+            "billing_api/invoice",
+            Some(ArcValueType::new_primitive(12.5)),
+        )
+        .await
+        .unwrap();
+        node.request(
+            "billing_api/audited_invoice",
+            Some(ArcValueType::new_primitive(7.5)),
+        )
+        .await
+        .unwrap();
+
+        // Give the events time to be delivered
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let store = store.lock().await;
+        let mut invoiced = store.get("invoiced").unwrap().clone();
+        assert_eq!(invoiced.as_type::<f64>().unwrap(), 12.5);
+        let mut audited = store.get("audited").unwrap().clone();
+        assert_eq!(audited.as_type::<f64>().unwrap(), 7.5);
+    }
+}
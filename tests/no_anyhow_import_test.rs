@@ -0,0 +1,100 @@
+// Test that the generated code does not depend on `anyhow!` being imported
+//
+// This module only imports `anyhow::Result`, so the code generated by the
+// action and subscribe macros must use fully qualified `anyhow::anyhow!` calls.
+
+use anyhow::Result;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, publish, service, subscribe};
+use runar_node::services::{EventContext, RequestContext};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Greeting {
+    message: String,
+}
+
+// Domain type received by subscriptions that convert from the wire `Greeting`
+#[derive(Debug, Clone, PartialEq)]
+struct Salutation(String);
+
+impl TryFrom<Greeting> for Salutation {
+    type Error = String;
+
+    fn try_from(greeting: Greeting) -> std::result::Result<Self, Self::Error> {
+        if greeting.message.is_empty() {
+            return Err("empty greeting".to_string());
+        }
+        Ok(Salutation(greeting.message))
+    }
+}
+
+#[derive(Clone)]
+pub struct GreeterService;
+
+#[service(name = "Greeter Service", path = "greeter")]
+impl GreeterService {
+    #[publish(path = "greeted", delay_ms = 10)]
+    #[action]
+    async fn greet(&self, name: String, ctx: &RequestContext) -> Result<Greeting> {
+        ctx.debug(format!("Greeting {}", name));
+        Ok(Greeting {
+            message: format!("Hello, {}!", name),
+        })
+    }
+
+    #[action]
+    async fn add(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
+        ctx.debug(format!("Adding {} + {}", a, b));
+        Ok(a + b)
+    }
+
+    #[subscribe(path = "greeter/greeted")]
+    async fn on_greeted(&self, greeting: Greeting, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("greeted: {}", greeting.message));
+        Ok(())
+    }
+
+    #[subscribe(path = "greeter/greeted", via = Greeting)]
+    async fn on_salutation(&self, salutation: Salutation, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("salutation: {}", salutation.0));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_node::Node;
+    use runar_node::NodeConfig;
+
+    #[tokio::test]
+    async fn test_actions_without_anyhow_import() {
+        let mut config = NodeConfig::new("test-node", "test_network");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        node.add_service(GreeterService).await.unwrap();
+        node.start().await.unwrap();
+
+        let response = node
+            .request(
+                "greeter/greet",
+                Some(ArcValueType::new_primitive("Runar".to_string())),
+            )
+            .await
+            .unwrap();
+        let greeting = response.unwrap().as_type::<Greeting>().unwrap();
+        assert_eq!(greeting.message, "Hello, Runar!");
+
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), 2.0);
+        map.insert("b".to_string(), 3.0);
+        let response = node
+            .request("greeter/add", Some(ArcValueType::new_map(map)))
+            .await
+            .unwrap();
+        assert_eq!(response.unwrap().as_type::<f64>().unwrap(), 5.0);
+    }
+}
@@ -0,0 +1,122 @@
+// Test for the ordered_by subscribe option
+//
+// Events sharing a key are handled one after the other, in the order they
+// were published, while an event with another key doesn't wait for them.
+// The order holds when a dedup check is awaited before the handler.
+
+use anyhow::Result;
+use futures::lock::Mutex;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, service, subscribe, test_node};
+use runar_node::services::{EventContext, RequestContext};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AccountUpdate {
+    account: String,
+    seq: u32,
+    work_ms: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Transfer {
+    id: String,
+    account: String,
+    work_ms: u64,
+}
+
+#[derive(Clone)]
+pub struct LedgerService {
+    handled: Arc<Mutex<Vec<String>>>,
+}
+
+#[service(name = "Ledger", path = "ledger")]
+impl LedgerService {
+    #[action]
+    async fn update(&self, update: AccountUpdate, ctx: &RequestContext) -> Result<()> {
+        ctx.publish("updated", Some(ArcValueType::from_struct(update)))
+            .await
+    }
+
+    #[subscribe(path = "ledger/updated", ordered_by = "account")]
+    async fn on_updated(&self, update: AccountUpdate, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("Applying {} #{}", update.account, update.seq));
+        tokio::time::sleep(Duration::from_millis(update.work_ms)).await;
+        self.handled
+            .lock()
+            .await
+            .push(format!("{}#{}", update.account, update.seq));
+        Ok(())
+    }
+
+    #[action]
+    async fn submit(&self, transfer: Transfer, ctx: &RequestContext) -> Result<()> {
+        ctx.publish("submitted", Some(ArcValueType::from_struct(transfer)))
+            .await
+    }
+
+    // The dedup check awaits the node's store before the event waits for its key
+    #[subscribe(
+        path = "ledger/submitted",
+        ordered_by = "account",
+        dedup_store = "memory",
+        dedup_key = "id"
+    )]
+    async fn on_submitted(&self, transfer: Transfer, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("Applying transfer {}", transfer.id));
+        tokio::time::sleep(Duration::from_millis(transfer.work_ms)).await;
+        self.handled.lock().await.push(transfer.id);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_ordered_by_key() {
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let node = test_node!(LedgerService {
+        handled: handled.clone()
+    });
+
+    for (account, seq, work_ms) in [("alice", 1, 100), ("alice", 2, 0), ("bob", 1, 0)] {
+        let update = AccountUpdate {
+            account: account.to_string(),
+            seq,
+            work_ms,
+        };
+        node.request("ledger/update", Some(ArcValueType::from_struct(update)))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // alice#2 waits for the slow alice#1, bob#1 finishes while alice#1 is still running
+    assert_eq!(*handled.lock().await, vec!["bob#1", "alice#1", "alice#2"]);
+}
+
+#[tokio::test]
+async fn test_ordered_by_key_with_dedup() {
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let node = test_node!(LedgerService {
+        handled: handled.clone()
+    });
+
+    // Published back to back, so later events reach the dedup check while earlier ones
+    // are still waiting on it
+    for (id, work_ms) in [("t1", 50), ("t2", 0), ("t3", 0)] {
+        let transfer = Transfer {
+            id: id.to_string(),
+            account: "alice".to_string(),
+            work_ms,
+        };
+        node.request("ledger/submit", Some(ArcValueType::from_struct(transfer)))
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // The events keep the order they were delivered in, however long each dedup check took
+    assert_eq!(*handled.lock().await, vec!["t1", "t2", "t3"]);
+}
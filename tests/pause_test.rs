@@ -0,0 +1,74 @@
+// Test for the start_paused subscribe option
+//
+// The pause flag is shared by every instance of the service type and reset
+// when an instance is initialized, so this fixture has a service of its own
+// and a single test flipping the flag.
+
+use anyhow::Result;
+use futures::lock::Mutex;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, service, subscribe, test_node};
+use runar_node::services::{EventContext, RequestContext};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct JobService {
+    runs: Arc<Mutex<Vec<String>>>,
+}
+
+#[service(name = "Jobs", path = "jobs")]
+impl JobService {
+    #[action]
+    async fn trigger(&self, job: String, ctx: &RequestContext) -> Result<()> {
+        ctx.publish("triggered", Some(ArcValueType::new_primitive(job)))
+            .await
+    }
+
+    // Registered at init, but only handles events once resumed
+    #[subscribe(path = "jobs/triggered", start_paused = true)]
+    async fn on_triggered(&self, job: String, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("Running job {}", job));
+        self.runs.lock().await.push(job);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_paused_subscription() {
+    let runs = Arc::new(Mutex::new(Vec::new()));
+    let node = test_node!(JobService { runs: runs.clone() });
+    let paused = || JobService::subscription_paused_on_triggered().load(Ordering::Acquire);
+    let trigger = |job: &str| {
+        node.request(
+            "jobs/trigger",
+            Some(ArcValueType::new_primitive(job.to_string())),
+        )
+    };
+    let settle = || tokio::time::sleep(std::time::Duration::from_millis(50));
+
+    // Events published while paused are dropped
+    assert!(paused());
+    trigger("backup").await.unwrap();
+    settle().await;
+    assert!(runs.lock().await.is_empty());
+
+    JobService::resume_on_triggered();
+    assert!(!paused());
+    trigger("cleanup").await.unwrap();
+    settle().await;
+    assert_eq!(*runs.lock().await, vec!["cleanup".to_string()]);
+
+    JobService::pause_on_triggered();
+    assert!(paused());
+    trigger("report").await.unwrap();
+    settle().await;
+    assert_eq!(*runs.lock().await, vec!["cleanup".to_string()]);
+
+    // A new instance starts paused again, even after the flag was resumed
+    JobService::resume_on_triggered();
+    let _second = test_node!(JobService {
+        runs: Arc::new(Mutex::new(Vec::new()))
+    });
+    assert!(paused());
+}
@@ -0,0 +1,120 @@
+// Test that actions declaring a JSON schema validate their parameters
+//
+// Requires the `schema_validation` feature; the schema file path is relative
+// to the crate root.
+
+use anyhow::Result;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, service};
+use runar_node::services::RequestContext;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// Only deserialized: the schema checks the payload, not the extracted parameter
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScaleParams {
+    pub value: f64,
+    pub factor: f64,
+}
+
+#[derive(Clone)]
+pub struct CalculatorService;
+
+#[service(name = "Calculator Service", path = "calculator")]
+impl CalculatorService {
+    #[action(schema = "tests/schemas/add.json")]
+    async fn add(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
+        ctx.debug(format!("Adding {} + {}", a, b));
+        Ok(a + b)
+    }
+
+    #[action(schema = "tests/schemas/scale.json")]
+    async fn scale(&self, params: ScaleParams, ctx: &RequestContext) -> Result<f64> {
+        ctx.debug(format!("Scaling {} by {}", params.value, params.factor));
+        Ok(params.value * params.factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_node::Node;
+    use runar_node::NodeConfig;
+
+    #[tokio::test]
+    async fn test_schema_validation() {
+        let mut config = NodeConfig::new("test-node", "test_network");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        node.add_service(CalculatorService).await.unwrap();
+        node.start().await.unwrap();
+
+        // Parameters matching the schema run the action
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), 2.0);
+        map.insert("b".to_string(), 3.0);
+        let response = node
+            .request("calculator/add", Some(ArcValueType::new_map(map)))
+            .await
+            .unwrap();
+        assert_eq!(response.unwrap().as_type::<f64>().unwrap(), 5.0);
+
+        // Parameters violating the schema are rejected with the failing location
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), -2.0);
+        map.insert("b".to_string(), 3.0);
+        let err = node
+            .request("calculator/add", Some(ArcValueType::new_map(map)))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Validation failed for action 'add'"));
+        assert!(err.contains("/a"));
+    }
+    #[tokio::test]
+    async fn test_schema_validates_payload() {
+        let mut config = NodeConfig::new("test-node", "test_network");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        node.add_service(CalculatorService).await.unwrap();
+        node.start().await.unwrap();
+
+        let mut map = HashMap::new();
+        map.insert("value".to_string(), 2.0);
+        map.insert("factor".to_string(), 3.0);
+        let response = node
+            .request("calculator/scale", Some(ArcValueType::new_map(map)))
+            .await
+            .unwrap();
+        assert_eq!(response.unwrap().as_type::<f64>().unwrap(), 6.0);
+
+        // Unknown fields would be dropped by the extraction, the schema sees them
+        let mut map = HashMap::new();
+        map.insert("value".to_string(), 2.0);
+        map.insert("factor".to_string(), 3.0);
+        map.insert("offset".to_string(), 1.0);
+        let err = node
+            .request("calculator/scale", Some(ArcValueType::new_map(map)))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Validation failed for action 'scale'"));
+
+        // A value of the wrong type fails validation rather than deserialization
+        let mut map = HashMap::new();
+        map.insert(
+            "value".to_string(),
+            ArcValueType::new_primitive("two".to_string()),
+        );
+        map.insert("factor".to_string(), ArcValueType::new_primitive(3.0));
+        let err = node
+            .request("calculator/scale", Some(ArcValueType::new_map(map)))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Validation failed for action 'scale'"));
+        assert!(err.contains("/value"));
+    }
+}
@@ -0,0 +1,71 @@
+// Test for the trait and impl_trait service attributes
+//
+// Two services expose the same action set through a generated trait, so
+// callers can use either backend as a trait object.
+
+use anyhow::Result;
+use runar_macros::{action, service};
+use runar_node::services::RequestContext;
+
+mod exact {
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct ExactCalculator;
+
+    #[service(name = "Exact Calculator", path = "exact", trait = "Calculator")]
+    impl ExactCalculator {
+        /// Add two numbers
+        #[action]
+        async fn add(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
+            ctx.debug(format!("Adding {} + {}", a, b));
+            Ok(a + b)
+        }
+
+        #[action(path = "halve")]
+        async fn half(&self, mut value: f64, ctx: &RequestContext) -> Result<f64> {
+            ctx.debug(format!("Halving {}", value));
+            value /= 2.0;
+            Ok(value)
+        }
+    }
+}
+
+mod rounded {
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct RoundedCalculator;
+
+    #[service(
+        name = "Rounded Calculator",
+        path = "rounded",
+        impl_trait = "super::exact::Calculator"
+    )]
+    impl RoundedCalculator {
+        #[action]
+        async fn add(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
+            ctx.debug(format!("Adding {} + {} rounded", a, b));
+            Ok((a + b).round())
+        }
+
+        #[action(path = "halve")]
+        async fn half(&self, value: f64, ctx: &RequestContext) -> Result<f64> {
+            ctx.debug(format!("Halving {} rounded", value));
+            Ok((value / 2.0).round())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::exact::{Calculator, ExactCalculator};
+    use super::rounded::RoundedCalculator;
+
+    #[test]
+    fn test_services_share_the_action_trait() {
+        let backends: Vec<Box<dyn Calculator>> =
+            vec![Box::new(ExactCalculator), Box::new(RoundedCalculator)];
+        assert_eq!(backends.len(), 2);
+    }
+}
@@ -0,0 +1,90 @@
+// Test for types shared across services
+//
+// Both services return the same type, so both register it with the node's
+// serializer at init. The second registration must not fail the node start.
+
+use anyhow::Result;
+use runar_macros::{action, service, test_node};
+use runar_node::services::RequestContext;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Money {
+    pub cents: i64,
+    pub currency: String,
+}
+
+mod pricing {
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct PricingService;
+
+    #[service(name = "Pricing", path = "pricing")]
+    impl PricingService {
+        #[action]
+        async fn quote(&self, item: String, ctx: &RequestContext) -> Result<Money> {
+            ctx.debug(format!("Quoting {}", item));
+            Ok(Money {
+                cents: 1250,
+                currency: "EUR".to_string(),
+            })
+        }
+    }
+}
+
+mod refunds {
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct RefundService;
+
+    #[service(name = "Refunds", path = "refunds")]
+    impl RefundService {
+        #[action]
+        async fn refund(&self, amount: Money, ctx: &RequestContext) -> Result<Money> {
+            ctx.debug(format!("Refunding {} {}", amount.cents, amount.currency));
+            Ok(Money {
+                cents: -amount.cents,
+                currency: amount.currency,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pricing::PricingService;
+    use super::refunds::RefundService;
+    use super::*;
+    use runar_common::types::ArcValueType;
+
+    #[tokio::test]
+    async fn test_services_sharing_a_type_start() {
+        // Both services register Money when the node starts them
+        let node = test_node!(PricingService, RefundService);
+
+        let response = node
+            .request(
+                "pricing/quote",
+                Some(ArcValueType::new_primitive("coffee".to_string())),
+            )
+            .await
+            .unwrap();
+        let price = response.unwrap().as_type::<Money>().unwrap();
+        assert_eq!(price.cents, 1250);
+
+        let response = node
+            .request("refunds/refund", Some(ArcValueType::from_struct(price)))
+            .await
+            .unwrap();
+        let refund = response.unwrap().as_type::<Money>().unwrap();
+        assert_eq!(
+            refund,
+            Money {
+                cents: -1250,
+                currency: "EUR".to_string(),
+            }
+        );
+    }
+}
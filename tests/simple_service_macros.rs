@@ -3,13 +3,14 @@
 // This test demonstrates how to use the service and action macros
 // to create a simple service with actions.
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use futures::lock::Mutex;
 use runar_common::types::ArcValueType;
 use runar_macros::{action, publish, service, subscribe};
 use runar_node::services::{EventContext, RequestContext};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 struct MyData {
@@ -30,6 +31,79 @@ struct User {
     age: i32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Audit {
+    created_by: String,
+    revision: u32,
+}
+
+// Flattened fields are handled by serde, the macro must not assume a flat struct
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct AuditedUser {
+    #[serde(flatten)]
+    user: User,
+    #[serde(flatten)]
+    audit: Audit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Account {
+    username: String,
+    password_hash: String,
+}
+
+// Fields with serde defaults may be left out of the payload, aliased fields may be sent
+// under their former name
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct SearchQuery {
+    #[serde(alias = "query")]
+    term: String,
+    #[serde(default = "default_search_limit")]
+    limit: u32,
+    #[serde(default)]
+    include_archived: bool,
+}
+
+fn default_search_limit() -> u32 {
+    20
+}
+
+// An untagged enum, deserialized from the raw value of its payload entry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum StringOrId {
+    Id(i32),
+    Name(String),
+}
+
+// A point exchanged through the JSON text serializer below
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+// A serializer carrying values as JSON text inside a string primitive
+struct JsonTextSerde;
+
+impl JsonTextSerde {
+    fn encode<T: Serialize>(value: &T) -> Result<ArcValueType> {
+        Ok(ArcValueType::new_primitive(serde_json::to_string(value)?))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(value: ArcValueType) -> Result<T> {
+        let mut value = value;
+        Ok(serde_json::from_str(&value.as_type::<String>()?)?)
+    }
+}
+
+// A response-only type, the service never deserializes it
+#[derive(Debug, Serialize)]
+struct StoreSummary {
+    entries: usize,
+    keys: Vec<String>,
+}
+
 // Define a simple math service
 pub struct TestService {
     store: Arc<Mutex<HashMap<String, ArcValueType>>>,
@@ -68,6 +142,93 @@ impl TestService {
         Ok(data)
     }
 
+    // Define an action whose result holds a field only needed internally
+    #[action(redact = ["password_hash"])]
+    async fn get_account(&self, username: String, ctx: &RequestContext) -> Result<Account> {
+        ctx.debug(format!("get_account username: {}", username));
+        Ok(Account {
+            username,
+            password_hash: "5f4dcc3b".to_string(),
+        })
+    }
+
+    // Define an action whose map result holds a key only needed internally
+    #[action(redact = ["api_key"])]
+    async fn get_settings(&self, ctx: &RequestContext) -> Result<HashMap<String, String>> {
+        ctx.debug("get_settings".to_string());
+        let mut settings = HashMap::new();
+        settings.insert("theme".to_string(), "dark".to_string());
+        settings.insert("api_key".to_string(), "sk-123".to_string());
+        Ok(settings)
+    }
+
+    // Define an action returning named outputs instead of a positional tuple
+    #[action(outputs(sum, product))]
+    async fn sum_and_product(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<(f64, f64)> {
+        ctx.debug(format!("sum_and_product {} {}", a, b));
+        Ok((a + b, a * b))
+    }
+
+    // Define an action taking an untagged enum next to another parameter
+    #[action]
+    async fn find_user(
+        &self,
+        user: StringOrId,
+        verbose: bool,
+        ctx: &RequestContext,
+    ) -> Result<String> {
+        ctx.debug(format!("find_user user: {:?}, verbose: {}", user, verbose));
+        Ok(match user {
+            StringOrId::Id(id) => format!("user #{}", id),
+            StringOrId::Name(name) => format!("user {}", name),
+        })
+    }
+
+    // Define an action returning a type that only implements Serialize
+    #[action]
+    async fn get_store_summary(&self, ctx: &RequestContext) -> Result<StoreSummary> {
+        let lock = self.store.lock().await;
+        let mut keys = lock.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        ctx.debug(format!("get_store_summary entries: {}", keys.len()));
+        Ok(StoreSummary {
+            entries: keys.len(),
+            keys,
+        })
+    }
+
+    // Define an action taking a struct whose omitted fields get serde defaults
+    #[action]
+    async fn search(&self, query: SearchQuery, ctx: &RequestContext) -> Result<SearchQuery> {
+        ctx.debug(format!("search term: {}", query.term));
+        Ok(query)
+    }
+
+    // Define an action returning a struct with flattened fields
+    #[action]
+    async fn get_audited_user(&self, id: i32, ctx: &RequestContext) -> Result<AuditedUser> {
+        ctx.debug(format!("get_audited_user id: {}", id));
+        Ok(AuditedUser {
+            user: User {
+                id,
+                name: "John Doe".to_string(),
+                email: String::new(),
+                age: 0,
+            },
+            audit: Audit {
+                created_by: "admin".to_string(),
+                revision: 3,
+            },
+        })
+    }
+
+    // Define an action without parameters, callable without a payload
+    #[action]
+    async fn ping(&self, ctx: &RequestContext) -> Result<String> {
+        ctx.debug("ping".to_string());
+        Ok("pong".to_string())
+    }
+
     #[action]
     async fn get_user(&self, id: i32, ctx: &RequestContext) -> Result<User> {
         let user = User {
@@ -79,6 +240,19 @@ impl TestService {
         Ok(user)
     }
 
+    // Publish the updated user to its own topic and to the shared one
+    #[publish(paths_from = |user| vec![format!("users/{}/updated", user.id), "users/updated".to_string()])]
+    #[action]
+    async fn rename_user(&self, id: i32, name: String, ctx: &RequestContext) -> Result<User> {
+        ctx.debug(format!("Renaming user {} to {}", id, name));
+        Ok(User {
+            id,
+            name,
+            email: String::new(),
+            age: 0,
+        })
+    }
+
     //the publish macro will do a ctx.publish("my_data_auto", ArcValueType::from_struct(action_result.clone())).await?;
     //it will publish the result of the action o the path (full or relative) same ruleas as action, subscribe macros in termos fo topic rules.,
     #[publish(path = "my_data_auto")]
@@ -116,6 +290,13 @@ impl TestService {
         .await?;
         ctx.publish("age_changed", Some(ArcValueType::new_primitive(25)))
             .await?;
+        ctx.publish(
+            "name_changed",
+            Some(ArcValueType::new_primitive("John Doe".to_string())),
+        )
+        .await?;
+        ctx.publish("verified_changed", Some(ArcValueType::new_primitive(true)))
+            .await?;
         Ok(data)
     }
 
@@ -161,6 +342,43 @@ impl TestService {
         Ok(())
     }
 
+    // At most two "added" events are counted at the same time, so the count doesn't
+    // depend on the order they are handled in
+    #[subscribe(path = "math/added", max_concurrency = 2)]
+    async fn on_added_counted(&self, total: f64, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("on_added_counted: {}", total));
+
+        let mut lock = self.store.lock().await;
+        let count = match lock.get("added_count") {
+            Some(existing) => existing.clone().as_type::<i32>()? + 1,
+            None => 1,
+        };
+        lock.insert(
+            "added_count".to_string(),
+            ArcValueType::new_primitive(count),
+        );
+        Ok(())
+    }
+
+    // The `{operation}` segment of the firing topic is bound to the parameter
+    #[subscribe(path = "math/{operation}/completed")]
+    async fn on_operation_completed(
+        &self,
+        operation: String,
+        result: f64,
+        ctx: &EventContext,
+    ) -> Result<()> {
+        ctx.debug(format!("{} completed: {}", operation, result));
+
+        let mut lock = self.store.lock().await;
+        lock.insert(
+            format!("completed_{}", operation),
+            ArcValueType::new_primitive(result),
+        );
+
+        Ok(())
+    }
+
     #[subscribe(path = "math/my_data_changed")]
     async fn on_my_data_changed(&self, data: MyData, ctx: &EventContext) -> Result<()> {
         ctx.debug(format!("my_data_changed: {}", data.text_field));
@@ -185,6 +403,58 @@ impl TestService {
         Ok(())
     }
 
+    // Only the first delivery of an event id is handled, across all service instances
+    #[subscribe(
+        path = "math/my_data_changed",
+        dedup_store = "memory://my_data",
+        dedup_key = "id"
+    )]
+    async fn on_my_data_deduped(&self, data: MyData, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("my_data_changed (deduplicated): {}", data.id));
+        self.store.lock().await.insert(
+            "my_data_deduped".to_string(),
+            ArcValueType::new_primitive(data.id),
+        );
+        Ok(())
+    }
+
+    #[subscribe(path = "math/users/7/updated")]
+    async fn on_user_updated(&self, user: User, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("user_updated: {}", user.name));
+        self.store
+            .lock()
+            .await
+            .insert("user_updated".to_string(), ArcValueType::from_struct(user));
+        Ok(())
+    }
+
+    // One handler at a time, at most eight events wait and older ones are dropped
+    #[subscribe(
+        path = "math/subtracted",
+        max_concurrency = 1,
+        buffer_full = "drop_oldest",
+        buffer_size = 8
+    )]
+    async fn on_subtracted(&self, difference: f64, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("subtracted: {}", difference));
+        self.store.lock().await.insert(
+            "subtracted".to_string(),
+            ArcValueType::new_primitive(difference),
+        );
+        Ok(())
+    }
+
+    // A panicking handler is reported as a handler error instead of unwinding into the node
+    #[subscribe(path = "math/age_changed", catch_panics = true)]
+    async fn on_age_audited(&self, new_age: i32, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("auditing age: {}", new_age));
+        self.store.lock().await.insert(
+            "age_audited".to_string(),
+            ArcValueType::new_primitive(new_age),
+        );
+        panic!("age audit is not implemented");
+    }
+
     #[subscribe(path = "math/age_changed")]
     async fn on_age_changed(&self, new_age: i32, ctx: &EventContext) -> Result<()> {
         ctx.debug(format!("age_changed: {}", new_age));
@@ -206,9 +476,46 @@ impl TestService {
         Ok(())
     }
 
+    // Primitive payloads published with new_primitive are read directly
+    #[subscribe(path = "math/cache_cleared")]
+    async fn on_cache_cleared(&self, marker: String, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("cache_cleared: {}", marker));
+        self.store.lock().await.insert(
+            "cache_cleared".to_string(),
+            ArcValueType::new_primitive(marker),
+        );
+        Ok(())
+    }
+
+    #[subscribe(path = "math/name_changed")]
+    async fn on_name_changed(&self, name: String, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("name_changed: {}", name));
+        self.store.lock().await.insert(
+            "name_changed".to_string(),
+            ArcValueType::new_primitive(name),
+        );
+        Ok(())
+    }
+
+    #[subscribe(path = "math/name_changed")]
+    async fn on_name_logged(&self, name: &str, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("name_changed (borrowed): {}", name));
+        Ok(())
+    }
+
+    #[subscribe(path = "math/verified_changed")]
+    async fn on_verified_changed(&self, verified: bool, ctx: &EventContext) -> Result<()> {
+        ctx.debug(format!("verified_changed: {}", verified));
+        self.store.lock().await.insert(
+            "verified_changed".to_string(),
+            ArcValueType::new_primitive(verified),
+        );
+        Ok(())
+    }
+
     // Define an action using the action macro
     #[publish(path = "added")]
-    #[action]
+    #[action(params_struct = true)]
     async fn add(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
         // Log using the context
         ctx.debug(format!("Adding {} + {}", a, b));
@@ -216,8 +523,9 @@ impl TestService {
         Ok(a + b)
     }
 
-    // Define another action
+    // Define another action, #[publish] also works below #[action]
     #[action]
+    #[publish(path = "subtracted")]
     async fn subtract(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
         // Log using the context
         ctx.debug(format!("Subtracting {} - {}", a, b));
@@ -226,6 +534,47 @@ impl TestService {
         Ok(a - b)
     }
 
+    // Define an action receiving a file upload from a binary part of the request
+    #[action]
+    async fn upload_avatar(
+        &self,
+        user_id: String,
+        #[file] avatar: Vec<u8>,
+        ctx: &RequestContext,
+    ) -> Result<usize> {
+        ctx.debug(format!(
+            "Storing a {} byte avatar for {}",
+            avatar.len(),
+            user_id
+        ));
+        Ok(avatar.len())
+    }
+
+    // Define an action reading its parameters by position from a list payload
+    #[action(positional = true, params_struct = true)]
+    async fn power(&self, base: f64, exponent: i32, ctx: &RequestContext) -> Result<f64> {
+        ctx.debug(format!("Raising {} to {}", base, exponent));
+        Ok(base.powi(exponent))
+    }
+
+    // Define an action converting its parameter and result with a custom serializer
+    #[action(serializer = "JsonTextSerde")]
+    async fn scale_point(&self, factor: f64, ctx: &RequestContext) -> Result<Point> {
+        ctx.debug(format!("Scaling the unit point by {}", factor));
+        Ok(Point {
+            x: factor,
+            y: factor,
+        })
+    }
+
+    // Define a unit-returning action publishing a fixed marker built from its input
+    #[action]
+    #[publish(path = "cache_cleared", value = format!("{} cleared", scope))]
+    async fn clear_cache(&self, scope: String, ctx: &RequestContext) -> Result<()> {
+        ctx.debug(format!("Clearing the {} cache", scope));
+        Ok(())
+    }
+
     // Define an action with a custom name
     #[action("multiply_numbers")]
     async fn multiply(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
@@ -236,6 +585,241 @@ impl TestService {
         Ok(a * b)
     }
 
+    // Define an action taking a Cow<str> parameter
+    #[action]
+    async fn greet(
+        &self,
+        greeting: Cow<'_, str>,
+        name: String,
+        ctx: &RequestContext,
+    ) -> Result<String> {
+        ctx.debug(format!("Greeting {}", name));
+        Ok(format!("{}, {}!", greeting, name))
+    }
+
+    // Define an action taking a list of custom structs
+    #[action]
+    async fn count_my_data(
+        &self,
+        items: Vec<MyData>,
+        min_id: i32,
+        ctx: &RequestContext,
+    ) -> Result<i32> {
+        ctx.debug(format!("Counting {} items", items.len()));
+        Ok(items.iter().filter(|item| item.id >= min_id).count() as i32)
+    }
+
+    // Define an action returning a map keyed by integers
+    #[action]
+    async fn index_my_data(
+        &self,
+        items: Vec<MyData>,
+        ctx: &RequestContext,
+    ) -> Result<HashMap<u32, MyData>> {
+        ctx.debug(format!("Indexing {} items", items.len()));
+        Ok(items
+            .into_iter()
+            .map(|item| (item.id as u32, item))
+            .collect())
+    }
+
+    // Define an action returning a binary payload
+    #[action]
+    async fn encode_bytes(&self, text: String, ctx: &RequestContext) -> Result<Vec<u8>> {
+        ctx.debug(format!("Encoding {}", text));
+        Ok(text.into_bytes())
+    }
+
+    // Define an action with more parameters than clippy allows by default, the
+    // generated code doesn't add to the lint
+    #[action]
+    #[allow(clippy::too_many_arguments)]
+    async fn evaluate_polynomial(
+        &self,
+        x: f64,
+        c0: f64,
+        c1: f64,
+        c2: f64,
+        c3: f64,
+        c4: f64,
+        c5: f64,
+        ctx: &RequestContext,
+    ) -> Result<f64> {
+        ctx.debug(format!("Evaluating polynomial at {}", x));
+        Ok([c0, c1, c2, c3, c4, c5]
+            .iter()
+            .rev()
+            .fold(0.0, |acc, c| acc * x + c))
+    }
+
+    // Define an action that forwards the payload without deserializing it
+    #[action(raw_payload)]
+    async fn echo(&self, payload: ArcValueType, ctx: &RequestContext) -> Result<ArcValueType> {
+        ctx.debug("Echoing raw payload".to_string());
+        Ok(payload)
+    }
+
+    // Define an action receiving the node-assigned request id
+    #[action]
+    async fn trace_request(
+        &self,
+        label: String,
+        #[request_id] id: String,
+        ctx: &RequestContext,
+    ) -> Result<String> {
+        ctx.debug(format!("Tracing {} in request {}", label, id));
+        Ok(id)
+    }
+
+    // Define a long-running action that stops when the caller cancels the request
+    #[action]
+    async fn count_up(
+        &self,
+        limit: u32,
+        #[cancel] token: CancellationToken,
+        _ctx: &RequestContext,
+    ) -> Result<u32> {
+        let mut count = 0;
+        while count < limit && !token.is_cancelled() {
+            count += 1;
+            tokio::task::yield_now().await;
+        }
+        Ok(count)
+    }
+
+    // Define an action handling a secret, argument values stay out of error messages
+    #[action(log_args = false)]
+    async fn set_password(
+        &self,
+        username: String,
+        password: String,
+        ctx: &RequestContext,
+    ) -> Result<bool> {
+        ctx.info(format!("Setting the password of {}", username));
+        Ok(!password.is_empty())
+    }
+
+    // Define an action whose data parameters have context-like names
+    #[action]
+    async fn describe_key(
+        &self,
+        #[param] context: String,
+        #[param] encryption_ctx: String,
+        ctx: &RequestContext,
+    ) -> Result<String> {
+        ctx.debug(format!("Describing key for {}", context));
+        Ok(format!("{}:{}", context, encryption_ctx))
+    }
+
+    // Define an action with a renamed parameter, old callers still send `factor`
+    #[action]
+    async fn scale(
+        &self,
+        value: f64,
+        #[alias("factor")] multiplier: f64,
+        ctx: &RequestContext,
+    ) -> Result<f64> {
+        ctx.debug(format!("Scaling {} by {}", value, multiplier));
+        Ok(value * multiplier)
+    }
+
+    // Define an action reading the order id from the route, or from the payload otherwise
+    #[action(path = "orders/{order_id}")]
+    async fn get_order(
+        &self,
+        #[from_path_or_payload] order_id: String,
+        verbose: bool,
+        ctx: &RequestContext,
+    ) -> Result<String> {
+        ctx.debug(format!("Getting order {} (verbose: {})", order_id, verbose));
+        Ok(if verbose {
+            format!("order {} (full)", order_id)
+        } else {
+            format!("order {}", order_id)
+        })
+    }
+
+    // Define an action whose only parameter may come from the path
+    #[action]
+    async fn order_status(
+        &self,
+        #[from_path_or_payload] order_id: u64,
+        ctx: &RequestContext,
+    ) -> Result<String> {
+        ctx.debug(format!("Getting status of order {}", order_id));
+        Ok(format!("order {} shipped", order_id))
+    }
+
+    // Define an action reporting its progress while it computes, its context has another name
+    #[action(progress_topic = "jobs/{job_id}/progress")]
+    async fn run_job(
+        &self,
+        job_id: String,
+        steps: u32,
+        request_ctx: &RequestContext,
+    ) -> Result<u32> {
+        request_ctx.debug(format!("Running job {} in {} steps", job_id, steps));
+        for step in 1..=steps {
+            progress.emit(step).await?;
+        }
+        self.store.lock().await.insert(
+            "job_progress_topic".to_string(),
+            ArcValueType::new_primitive(progress.topic().to_string()),
+        );
+        Ok(steps)
+    }
+
+    // Define an action returning its list one page at a time
+    #[action(paginate = true)]
+    async fn list_squares(&self, count: u64, ctx: &RequestContext) -> Result<Vec<u64>> {
+        ctx.debug(format!("Listing {} squares", count));
+        Ok((0..count).map(|n| n * n).collect())
+    }
+
+    // Define an action that gives up after 50 ms, releasing its reservation
+    #[action(timeout_ms = 50, on_timeout = "release_reservation")]
+    async fn reserve_slot(&self, slot: String, ctx: &RequestContext) -> Result<String> {
+        ctx.debug(format!("Reserving slot {}", slot));
+        self.store.lock().await.insert(
+            "reserved_slot".to_string(),
+            ArcValueType::new_primitive(slot.clone()),
+        );
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        Ok(slot)
+    }
+
+    // Cleanup hook for reserve_slot, called when it times out
+    async fn release_reservation(&self, ctx: &RequestContext) {
+        ctx.debug("Releasing the timed out reservation".to_string());
+        self.store.lock().await.remove("reserved_slot");
+    }
+
+    // Define an action capped by its own timeout or the caller's deadline, whichever is sooner
+    #[action(timeout_ms = 1000, respect_deadline = true)]
+    async fn lookup_slot(&self, slot: String, ctx: &RequestContext) -> Result<bool> {
+        ctx.debug(format!("Looking up slot {}", slot));
+        Ok(self.store.lock().await.contains_key("reserved_slot"))
+    }
+
+    // Define an action whose retries are deduplicated by request id
+    #[action(idempotency_key = "request_id")]
+    async fn record_payment(
+        &self,
+        request_id: String,
+        amount: f64,
+        ctx: &RequestContext,
+    ) -> Result<i32> {
+        ctx.debug(format!("Recording payment {} of {}", request_id, amount));
+
+        let mut lock = self.store.lock().await;
+        let count = match lock.get("payments") {
+            Some(existing) => existing.clone().as_type::<i32>()? + 1,
+            None => 1,
+        };
+        lock.insert("payments".to_string(), ArcValueType::new_primitive(count));
+        Ok(count)
+    }
+
     // Define an action that can fail
     #[action]
     async fn divide(&self, a: f64, b: f64, ctx: &RequestContext) -> Result<f64> {
@@ -261,6 +845,51 @@ mod tests {
     use runar_node::Node;
     use runar_node::NodeConfig;
 
+    #[test]
+    fn test_all_action_paths() {
+        let paths = TestService::all_action_paths();
+        for path in ["add", "subtract", "multiply_numbers", "divide", "my_data"] {
+            assert!(paths.contains(&path), "missing action path {}", path);
+        }
+        assert!(!paths.contains(&"on_added"));
+    }
+
+    #[test]
+    fn test_action_path_constants() {
+        assert_eq!(TestService::SUBTRACT_PATH, "math/subtract");
+        assert_eq!(TestService::MULTIPLY_NUMBERS_PATH, "math/multiply_numbers");
+    }
+
+    #[test]
+    fn test_log_args_annotation() {
+        // log_args = false is recorded with the annotations
+        let annotations = TestService::action_annotations();
+        assert_eq!(
+            annotations["set_password"].get("log_args").unwrap(),
+            "false"
+        );
+        assert!(!annotations["add"].contains_key("log_args"));
+    }
+
+    #[tokio::test]
+    async fn test_add_params() {
+        let mut config = NodeConfig::new("test-node", "test_network");
+        // Disable networking
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        let store = Arc::new(Mutex::new(HashMap::new()));
+        node.add_service(TestService::new("math", store))
+            .await
+            .unwrap();
+        node.start().await.unwrap();
+
+        // The generated params struct builds the same payload as the hand-built map
+        let params = AddParams { a: 10.0, b: 5.0 }.to_value();
+        let response = node.request("math/add", Some(params)).await.unwrap();
+        assert_eq!(response.unwrap().as_type::<f64>().unwrap(), 15.0);
+    }
+
     #[tokio::test]
     async fn test_math_service() {
         //set log to debug
@@ -307,6 +936,43 @@ mod tests {
         // Verify the response
         assert_eq!(response.unwrap().as_type::<f64>().unwrap(), 5.0);
 
+        // Make a request to the upload_avatar action without the binary part, it fails
+        let params = ArcValueType::new_primitive("jdoe".to_string());
+        let response = node.request("math/upload_avatar", Some(params)).await;
+        assert!(response
+            .unwrap_err()
+            .to_string()
+            .contains("Missing binary part avatar"));
+
+        // Make a request to the power action with a positional array payload
+        let params = ArcValueType::new_list(vec![
+            ArcValueType::new_primitive(2.0),
+            ArcValueType::new_primitive(10),
+        ]);
+        let response = node.request("math/power", Some(params)).await.unwrap();
+        assert_eq!(response.unwrap().as_type::<f64>().unwrap(), 1024.0);
+
+        // Its params struct builds the same list
+        let params = PowerParams {
+            base: 3.0,
+            exponent: 2,
+        }
+        .to_value();
+        let response = node.request("math/power", Some(params)).await.unwrap();
+        assert_eq!(response.unwrap().as_type::<f64>().unwrap(), 9.0);
+
+        // Make a request to the scale_point action, both ways go through JsonTextSerde
+        let params = ArcValueType::new_primitive("2.5".to_string());
+        let response = node
+            .request("math/scale_point", Some(params))
+            .await
+            .unwrap();
+        let text = response.unwrap().as_type::<String>().unwrap();
+        assert_eq!(
+            serde_json::from_str::<Point>(&text).unwrap(),
+            Point { x: 2.5, y: 2.5 }
+        );
+
         // Make a request to the multiply action (with custom name)
         let mut map = std::collections::HashMap::new();
         map.insert("a".to_string(), 5.0);
@@ -346,6 +1012,278 @@ mod tests {
             .to_string()
             .contains("Division by zero"));
 
+        // Make a request to the greet action with a Cow<str> parameter
+        let mut map = std::collections::HashMap::new();
+        map.insert("greeting".to_string(), "Hello".to_string());
+        map.insert("name".to_string(), "Runar".to_string());
+        let params = ArcValueType::new_map(map);
+
+        let response = node.request("math/greet", Some(params)).await.unwrap();
+
+        // Verify the response
+        assert_eq!(
+            response.unwrap().as_type::<String>().unwrap(),
+            "Hello, Runar!"
+        );
+
+        // Make a request to the trace_request action, the id comes from the context
+        let params = ArcValueType::new_primitive("trace".to_string());
+        let response = node
+            .request("math/trace_request", Some(params))
+            .await
+            .unwrap();
+
+        // Verify the request id was populated
+        assert!(!response.unwrap().as_type::<String>().unwrap().is_empty());
+
+        // Make a request to the count_up action, the token comes from the context
+        let params = ArcValueType::new_primitive(3u32);
+        let response = node.request("math/count_up", Some(params)).await.unwrap();
+
+        // Verify the uncancelled request ran to completion
+        assert_eq!(response.unwrap().as_type::<u32>().unwrap(), 3);
+
+        // Make a request to the set_password action with a password of the wrong type
+        let mut map = std::collections::HashMap::new();
+        map.insert(
+            "username".to_string(),
+            ArcValueType::new_primitive("alice".to_string()),
+        );
+        map.insert("password".to_string(), ArcValueType::new_primitive(987654));
+        let params = ArcValueType::new_map(map);
+        let err = node
+            .request("math/set_password", Some(params))
+            .await
+            .unwrap_err();
+
+        // Verify the error names the parameter but never quotes its value
+        assert!(err.to_string().contains("password"));
+        assert!(!err.to_string().contains("987654"));
+
+        // Make a request to the rename_user action, which publishes to topics built from the result
+        let mut map = std::collections::HashMap::new();
+        map.insert("id".to_string(), ArcValueType::new_primitive(7));
+        map.insert(
+            "name".to_string(),
+            ArcValueType::new_primitive("Jane Doe".to_string()),
+        );
+        let params = ArcValueType::new_map(map);
+        node.request("math/rename_user", Some(params))
+            .await
+            .unwrap();
+
+        // Make a request to the get_account action, the password hash is left out
+        let params = ArcValueType::new_primitive("jdoe".to_string());
+        let response = node
+            .request("math/get_account", Some(params))
+            .await
+            .unwrap();
+        let account = response
+            .unwrap()
+            .as_map_ref::<String, ArcValueType>()
+            .unwrap();
+        assert!(account.contains_key("username"));
+        assert!(!account.contains_key("password_hash"));
+
+        // Make a request to the get_settings action, the api key is left out of the map
+        let response = node.request("math/get_settings", None).await.unwrap();
+        let settings = response.unwrap().as_map_ref::<String, String>().unwrap();
+        assert_eq!(settings.get("theme"), Some(&"dark".to_string()));
+        assert!(!settings.contains_key("api_key"));
+
+        // Make a request to the sum_and_product action, the outputs are named
+        let mut map = std::collections::HashMap::new();
+        map.insert("a".to_string(), 3.0);
+        map.insert("b".to_string(), 4.0);
+        let params = ArcValueType::new_map(map);
+        let response = node
+            .request("math/sum_and_product", Some(params))
+            .await
+            .unwrap();
+        let outputs = response.unwrap().as_map_ref::<String, f64>().unwrap();
+        assert_eq!(outputs.get("sum"), Some(&7.0));
+        assert_eq!(outputs.get("product"), Some(&12.0));
+
+        // Make requests to the find_user action with both shapes of the untagged enum
+        let mut map = std::collections::HashMap::new();
+        map.insert("user".to_string(), ArcValueType::new_primitive(7));
+        map.insert("verbose".to_string(), ArcValueType::new_primitive(true));
+        let params = ArcValueType::new_map(map);
+        let response = node.request("math/find_user", Some(params)).await.unwrap();
+        assert_eq!(response.unwrap().as_type::<String>().unwrap(), "user #7");
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(
+            "user".to_string(),
+            ArcValueType::new_primitive("jdoe".to_string()),
+        );
+        map.insert("verbose".to_string(), ArcValueType::new_primitive(false));
+        let params = ArcValueType::new_map(map);
+        let response = node.request("math/find_user", Some(params)).await.unwrap();
+        assert_eq!(response.unwrap().as_type::<String>().unwrap(), "user jdoe");
+
+        // Make a request to the get_store_summary action, its result is read as a map
+        let response = node.request("math/get_store_summary", None).await.unwrap();
+        let summary = response
+            .unwrap()
+            .as_map_ref::<String, ArcValueType>()
+            .unwrap();
+        assert!(summary.contains_key("entries"));
+        assert!(summary.contains_key("keys"));
+
+        // Make a request to the get_audited_user action
+        let params = ArcValueType::new_primitive(42);
+        let response = node
+            .request("math/get_audited_user", Some(params))
+            .await
+            .unwrap();
+
+        // Verify the flattened fields survive the round trip
+        let audited = response.unwrap().as_type::<AuditedUser>().unwrap();
+        assert_eq!(audited.user.id, 42);
+        assert_eq!(audited.user.name, "John Doe");
+        assert_eq!(audited.audit.created_by, "admin");
+        assert_eq!(audited.audit.revision, 3);
+
+        // Make a request to the search action with only the field that has no default
+        let mut map = std::collections::HashMap::new();
+        map.insert(
+            "term".to_string(),
+            ArcValueType::new_primitive("rust".to_string()),
+        );
+        let params = ArcValueType::new_map(map);
+        let response = node.request("math/search", Some(params)).await.unwrap();
+
+        // Verify the omitted fields were filled with their serde defaults
+        let query = response.unwrap().as_type::<SearchQuery>().unwrap();
+        assert_eq!(
+            query,
+            SearchQuery {
+                term: "rust".to_string(),
+                limit: 20,
+                include_archived: false,
+            }
+        );
+
+        // Make a request to the search action with a field sent under its serde alias
+        let mut map = std::collections::HashMap::new();
+        map.insert(
+            "query".to_string(),
+            ArcValueType::new_primitive("macros".to_string()),
+        );
+        map.insert("limit".to_string(), ArcValueType::new_primitive(5u32));
+        let params = ArcValueType::new_map(map);
+        let response = node.request("math/search", Some(params)).await.unwrap();
+        let query = response.unwrap().as_type::<SearchQuery>().unwrap();
+        assert_eq!(query.term, "macros");
+        assert_eq!(query.limit, 5);
+
+        // Make a request to the scale action with the parameter's current and former name
+        let mut map = std::collections::HashMap::new();
+        map.insert("value".to_string(), 3.0);
+        map.insert("multiplier".to_string(), 2.0);
+        let params = ArcValueType::new_map(map);
+        let response = node.request("math/scale", Some(params)).await.unwrap();
+        assert_eq!(response.unwrap().as_type::<f64>().unwrap(), 6.0);
+
+        let mut map = std::collections::HashMap::new();
+        map.insert("value".to_string(), 3.0);
+        map.insert("factor".to_string(), 4.0);
+        let params = ArcValueType::new_map(map);
+        let response = node.request("math/scale", Some(params)).await.unwrap();
+        assert_eq!(response.unwrap().as_type::<f64>().unwrap(), 12.0);
+
+        // Make requests to the get_order action, the path capture wins over the payload
+        let mut map = std::collections::HashMap::new();
+        map.insert(
+            "order_id".to_string(),
+            ArcValueType::new_primitive("9".to_string()),
+        );
+        map.insert("verbose".to_string(), ArcValueType::new_primitive(true));
+        let params = ArcValueType::new_map(map);
+        let response = node.request("math/orders/7", Some(params)).await.unwrap();
+        assert_eq!(
+            response.unwrap().as_type::<String>().unwrap(),
+            "order 7 (full)"
+        );
+
+        // Make a request to the order_status action with the order id in the payload
+        let params = ArcValueType::new_primitive(12u64);
+        let response = node
+            .request("math/order_status", Some(params))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.unwrap().as_type::<String>().unwrap(),
+            "order 12 shipped"
+        );
+
+        // Make a request to the run_job action, it reports progress to the job's topic
+        let mut map = std::collections::HashMap::new();
+        map.insert(
+            "job_id".to_string(),
+            ArcValueType::new_primitive("j1".to_string()),
+        );
+        map.insert("steps".to_string(), ArcValueType::new_primitive(3u32));
+        let params = ArcValueType::new_map(map);
+        let response = node.request("math/run_job", Some(params)).await.unwrap();
+        assert_eq!(response.unwrap().as_type::<u32>().unwrap(), 3);
+        let mut progress_topic = store
+            .lock()
+            .await
+            .get("job_progress_topic")
+            .unwrap()
+            .clone();
+        assert_eq!(
+            progress_topic.as_type::<String>().unwrap(),
+            "jobs/j1/progress"
+        );
+
+        // Make a request to the list_squares action for the second page of two
+        let mut map = std::collections::HashMap::new();
+        map.insert("count".to_string(), ArcValueType::new_primitive(5u64));
+        map.insert("cursor".to_string(), ArcValueType::new_primitive(2usize));
+        map.insert("limit".to_string(), ArcValueType::new_primitive(2usize));
+        let params = ArcValueType::new_map(map);
+        let response = node
+            .request("math/list_squares", Some(params))
+            .await
+            .unwrap();
+        let page = response
+            .unwrap()
+            .as_map_ref::<String, ArcValueType>()
+            .unwrap();
+        let mut items = page.get("items").unwrap().clone();
+        assert_eq!(items.as_type::<Vec<u64>>().unwrap(), vec![4, 9]);
+        let mut next_cursor = page.get("next_cursor").unwrap().clone();
+        assert_eq!(next_cursor.as_type::<u64>().unwrap(), 4);
+        let mut total = page.get("total").unwrap().clone();
+        assert_eq!(total.as_type::<u64>().unwrap(), 5);
+
+        // Make a request to the reserve_slot action, it times out and releases the slot
+        let params = ArcValueType::new_primitive("A1".to_string());
+        let response = node.request("math/reserve_slot", Some(params)).await;
+        assert!(response.unwrap_err().to_string().contains("timed out"));
+        assert!(!store.lock().await.contains_key("reserved_slot"));
+
+        // Make a request to the lookup_slot action, without a deadline only timeout_ms applies
+        let params = ArcValueType::new_primitive("A1".to_string());
+        let response = node
+            .request("math/lookup_slot", Some(params))
+            .await
+            .unwrap();
+        assert!(!response.unwrap().as_type::<bool>().unwrap());
+
+        // Make a request to the clear_cache action, it publishes a marker instead of ()
+        let params = ArcValueType::new_primitive("users".to_string());
+        node.request("math/clear_cache", Some(params))
+            .await
+            .unwrap();
+
+        // Make a request to the ping action without a payload
+        let response = node.request("math/ping", None).await.unwrap();
+        assert_eq!(response.unwrap().as_type::<String>().unwrap(), "pong");
+
         // Make a request to the get_user action
         let params = ArcValueType::new_primitive(42);
         let response = node.request("math/get_user", Some(params)).await.unwrap();
@@ -376,6 +1314,83 @@ mod tests {
             }
         );
 
+        // Make a request to the count_my_data action with a list of structs
+        let items = vec![
+            MyData {
+                id: 1,
+                ..my_data.clone()
+            },
+            MyData {
+                id: 5,
+                ..my_data.clone()
+            },
+        ];
+        let params = ArcValueType::new_map(HashMap::from([
+            ("items".to_string(), ArcValueType::new_list(items.clone())),
+            ("min_id".to_string(), ArcValueType::new_primitive(2)),
+        ]));
+        let response = node
+            .request("math/count_my_data", Some(params))
+            .await
+            .unwrap();
+
+        // Verify the response
+        assert_eq!(response.unwrap().as_type::<i32>().unwrap(), 1);
+
+        // Make a request to the index_my_data action and round-trip the integer-keyed map
+        let response = node
+            .request("math/index_my_data", Some(ArcValueType::new_list(items)))
+            .await
+            .unwrap();
+
+        // Verify the response
+        let index = response.unwrap().as_type::<HashMap<u32, MyData>>().unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index[&5].id, 5);
+
+        // Make a request to the encode_bytes action and read the raw bytes back
+        let response = node
+            .request(
+                "math/encode_bytes",
+                Some(ArcValueType::new_primitive("runar".to_string())),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            response.unwrap().as_type::<Vec<u8>>().unwrap(),
+            b"runar".to_vec()
+        );
+
+        // Make a request to the describe_key action, whose parameters look like contexts
+        let params = ArcValueType::new_map(HashMap::from([
+            ("context".to_string(), "billing".to_string()),
+            ("encryption_ctx".to_string(), "aes-256".to_string()),
+        ]));
+        let response = node
+            .request("math/describe_key", Some(params))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.unwrap().as_type::<String>().unwrap(),
+            "billing:aes-256"
+        );
+
+        // Retrying record_payment with the same request id returns the first response
+        for (request_id, expected_count) in [("payment-1", 1), ("payment-1", 1), ("payment-2", 2)] {
+            let params = ArcValueType::new_map(HashMap::from([
+                (
+                    "request_id".to_string(),
+                    ArcValueType::new_primitive(request_id.to_string()),
+                ),
+                ("amount".to_string(), ArcValueType::new_primitive(9.99)),
+            ]));
+            let response = node
+                .request("math/record_payment", Some(params))
+                .await
+                .unwrap();
+            assert_eq!(response.unwrap().as_type::<i32>().unwrap(), expected_count);
+        }
+
         // Let's assert all the events stored in our store
         let store = store.lock().await;
 
@@ -412,6 +1427,10 @@ mod tests {
             panic!("Expected 'added' key in store, but it wasn't found");
         }
 
+        // The concurrency-limited handler saw the same added events
+        let mut added_count = store.get("added_count").unwrap().clone();
+        assert_eq!(added_count.as_type::<i32>().unwrap(), 2);
+
         // Check for my_data_changed events
         if let Some(changed_arc) = store.get("my_data_changed") {
             let mut changed_arc = changed_arc.clone();
@@ -429,6 +1448,20 @@ mod tests {
             panic!("Expected 'my_data_changed' key in store, but it wasn't found");
         }
 
+        // Check that the clear_cache marker was published
+        let mut marker = store
+            .get("cache_cleared")
+            .expect("Expected 'cache_cleared' key in store")
+            .clone();
+        assert_eq!(marker.as_type::<String>().unwrap(), "users cleared");
+
+        // Check that the deduplicated subscription handled the event
+        let mut deduped = store
+            .get("my_data_deduped")
+            .expect("Expected 'my_data_deduped' key in store")
+            .clone();
+        assert_eq!(deduped.as_type::<i32>().unwrap(), my_data.id);
+
         // Check for age_changed events
         if let Some(age_arc) = store.get("age_changed") {
             let mut age_arc = age_arc.clone();
@@ -443,6 +1476,24 @@ mod tests {
         } else {
             panic!("Expected 'age_changed' key in store, but it wasn't found");
         }
+
+        // The panicking handler ran, and the other age_changed handler still got the event
+        let mut audited = store.get("age_audited").unwrap().clone();
+        assert_eq!(audited.as_type::<i32>().unwrap(), 25);
+
+        // Check the event published by an action with #[publish] below #[action]
+        let mut subtracted = store.get("subtracted").unwrap().clone();
+        assert_eq!(subtracted.as_type::<f64>().unwrap(), 5.0);
+
+        // Check the event published to the per-user topic
+        let mut user = store.get("user_updated").unwrap().clone();
+        assert_eq!(user.as_type::<User>().unwrap().name, "Jane Doe");
+
+        // Check the primitive-typed events
+        let mut name = store.get("name_changed").unwrap().clone();
+        assert_eq!(name.as_type::<String>().unwrap(), "John Doe");
+        let mut verified = store.get("verified_changed").unwrap().clone();
+        assert!(verified.as_type::<bool>().unwrap());
         //make sure type were added properly to the serializer
         let serializer = node.serializer.read().await;
         let arc_value = ArcValueType::from_struct(my_data.clone());
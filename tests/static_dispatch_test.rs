@@ -0,0 +1,89 @@
+// Test for the static_dispatch service attribute
+//
+// Each dispatcher holds the handlers of the instance it was built from, so
+// two instances of a service answer with their own state.
+
+use anyhow::Result;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, service, test_node};
+use runar_node::services::RequestContext;
+use std::sync::Arc;
+
+mod inventory {
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct InventoryService {
+        pub region: String,
+    }
+
+    #[service(name = "Inventory", path = "inventory", static_dispatch = true)]
+    impl InventoryService {
+        #[action]
+        async fn region(&self, ctx: &RequestContext) -> Result<String> {
+            ctx.debug(format!("Answering from {}", self.region));
+            Ok(self.region.clone())
+        }
+    }
+}
+
+mod gateway {
+    use super::inventory::InventoryServiceDispatcher;
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct GatewayService {
+        pub east: Arc<InventoryServiceDispatcher>,
+        pub west: Arc<InventoryServiceDispatcher>,
+    }
+
+    #[service(name = "Gateway", path = "gateway")]
+    impl GatewayService {
+        #[action]
+        async fn forward(&self, east: bool, path: String, ctx: &RequestContext) -> Result<String> {
+            let dispatcher = if east { &self.east } else { &self.west };
+            let mut response = dispatcher
+                .dispatch(&path, None, ctx.clone())
+                .await?
+                .unwrap();
+            response.as_type::<String>()
+        }
+    }
+}
+
+use gateway::GatewayService;
+use inventory::InventoryService;
+
+#[tokio::test]
+async fn test_dispatcher_per_instance() {
+    let dispatcher = |region: &str| {
+        let service = InventoryService {
+            region: region.to_string(),
+        };
+        Arc::new(service.dispatcher().unwrap())
+    };
+    let node = test_node!(GatewayService {
+        east: dispatcher("east"),
+        west: dispatcher("west"),
+    });
+
+    let forward = |east: bool, path: &str| {
+        let mut params = std::collections::HashMap::new();
+        params.insert("east".to_string(), ArcValueType::new_primitive(east));
+        params.insert(
+            "path".to_string(),
+            ArcValueType::new_primitive(path.to_string()),
+        );
+        node.request("gateway/forward", Some(ArcValueType::new_map(params)))
+    };
+
+    // Relative and service-prefixed paths reach the instance's own handler
+    let mut response = forward(true, "region").await.unwrap().unwrap();
+    assert_eq!(response.as_type::<String>().unwrap(), "east");
+    let mut response = forward(false, "inventory/region").await.unwrap().unwrap();
+    assert_eq!(response.as_type::<String>().unwrap(), "west");
+
+    // Unknown paths are reported
+    let err = forward(true, "stock").await.unwrap_err().to_string();
+    assert!(err.contains("No action found for path stock"), "{}", err);
+}
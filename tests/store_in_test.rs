@@ -0,0 +1,68 @@
+// Test for the store_in subscribe option
+//
+// The handler returns the value to keep and the macro stores it into the
+// named service field, so handlers that only stash the latest event need
+// no locking code of their own.
+
+use anyhow::Result;
+use runar_macros::{action, publish, service, subscribe};
+use runar_node::services::{EventContext, RequestContext};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+#[derive(Clone)]
+pub struct ThermostatService {
+    last_reading: Arc<RwLock<Option<f64>>>,
+}
+
+#[service(name = "Thermostat", path = "thermostat")]
+impl ThermostatService {
+    #[publish(path = "thermostat/reading")]
+    #[action]
+    async fn report(&self, celsius: f64, ctx: &RequestContext) -> Result<f64> {
+        ctx.debug(format!("Reporting {}", celsius));
+        Ok(celsius)
+    }
+
+    // The returned reading replaces the stored one
+    #[subscribe(path = "thermostat/reading", store_in = "last_reading")]
+    async fn on_reading(&self, celsius: f64, ctx: &EventContext) -> Result<Option<f64>> {
+        ctx.debug(format!("Reading {}", celsius));
+        Ok(Some(celsius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_common::types::ArcValueType;
+    use runar_node::Node;
+    use runar_node::NodeConfig;
+
+    #[tokio::test]
+    async fn test_store_in_keeps_latest_event() {
+        let mut config = NodeConfig::new("test-node", "test_network");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        let last_reading = Arc::new(RwLock::new(None));
+        let service = ThermostatService {
+            last_reading: last_reading.clone(),
+        };
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        for celsius in [19.5, 21.0] {
+            node.request(
+                "thermostat/report",
+                Some(ArcValueType::new_primitive(celsius)),
+            )
+            .await
+            .unwrap();
+            // Give the event time to be delivered
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+
+        assert_eq!(*last_reading.read().await, Some(21.0));
+    }
+}
@@ -0,0 +1,73 @@
+// Test for the test_node macro
+//
+// The macro builds a started node with the given services added, so a
+// test can send requests to them right away.
+
+use anyhow::Result;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, service, test_node};
+use runar_node::services::RequestContext;
+
+mod greeter {
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct GreeterService;
+
+    #[service(name = "Greeter", path = "greeter")]
+    impl GreeterService {
+        #[action]
+        async fn greet(&self, name: String, ctx: &RequestContext) -> Result<String> {
+            ctx.debug(format!("Greeting {}", name));
+            Ok(format!("Hello, {}!", name))
+        }
+    }
+}
+
+mod counter {
+    use super::*;
+
+    #[derive(Clone)]
+    pub struct CounterService {
+        pub step: i32,
+    }
+
+    #[service(name = "Counter", path = "counter")]
+    impl CounterService {
+        #[action]
+        async fn next(&self, value: i32, ctx: &RequestContext) -> Result<i32> {
+            ctx.debug(format!("Counting from {}", value));
+            Ok(value + self.step)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::counter::CounterService;
+    use super::greeter::GreeterService;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_node_with_several_services() {
+        let node = test_node!(GreeterService, CounterService { step: 2 });
+
+        let response = node
+            .request(
+                "greeter/greet",
+                Some(ArcValueType::new_primitive("Ada".to_string())),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            response.unwrap().as_type::<String>().unwrap(),
+            "Hello, Ada!"
+        );
+
+        let response = node
+            .request("counter/next", Some(ArcValueType::new_primitive(40)))
+            .await
+            .unwrap();
+        assert_eq!(response.unwrap().as_type::<i32>().unwrap(), 42);
+    }
+}
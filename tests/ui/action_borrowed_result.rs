@@ -0,0 +1,19 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct GreeterService {
+    greeting: String,
+}
+
+impl GreeterService {
+    #[action]
+    async fn greeting(
+        &self,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<&str> {
+        ctx.debug("Reading greeting".to_string());
+        Ok(&self.greeting)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct DownloadService;
+
+impl DownloadService {
+    #[action]
+    async fn download(
+        &self,
+        url: String,
+        #[cancel] cancelled: bool,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<String> {
+        ctx.debug(format!("Downloading {} (cancelled: {})", url, cancelled));
+        Ok(url)
+    }
+}
+
+fn main() {}
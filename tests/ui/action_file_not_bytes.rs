@@ -0,0 +1,18 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct AvatarService;
+
+impl AvatarService {
+    #[action]
+    async fn upload(
+        &self,
+        #[file] avatar: String,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<usize> {
+        ctx.debug(format!("Uploading {}", avatar));
+        Ok(avatar.len())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct PaymentService;
+
+impl PaymentService {
+    #[action(idempotency_key = "request_id")]
+    async fn charge(
+        &self,
+        request_id: u64,
+        amount: f64,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<f64> {
+        ctx.debug(format!("Charging {} for {}", amount, request_id));
+        Ok(amount)
+    }
+}
+
+fn main() {}
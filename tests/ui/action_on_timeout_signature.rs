@@ -0,0 +1,22 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct BookingService;
+
+impl BookingService {
+    #[action(timeout_ms = 100, on_timeout = "release")]
+    async fn reserve(
+        &self,
+        slot: String,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<String> {
+        ctx.debug(format!("Reserving {}", slot));
+        Ok(slot)
+    }
+
+    async fn release(&self, slot: String) {
+        let _ = slot;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct MathService;
+
+impl MathService {
+    #[action(outputs(sum, product, quotient))]
+    async fn sum_and_product(
+        &self,
+        a: f64,
+        b: f64,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<(f64, f64)> {
+        ctx.debug(format!("sum_and_product {} {}", a, b));
+        Ok((a + b, a * b))
+    }
+}
+
+fn main() {}
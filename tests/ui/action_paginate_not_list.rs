@@ -0,0 +1,18 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct MathService;
+
+impl MathService {
+    #[action(paginate = true)]
+    async fn count(
+        &self,
+        limit: u64,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<u64> {
+        ctx.debug(format!("Counting to {}", limit));
+        Ok(limit)
+    }
+}
+
+fn main() {}
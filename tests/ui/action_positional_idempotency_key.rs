@@ -0,0 +1,19 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct MathService;
+
+impl MathService {
+    #[action(positional = true, idempotency_key = "request_id")]
+    async fn add(
+        &self,
+        request_id: String,
+        a: f64,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<f64> {
+        ctx.debug(format!("Adding {} for {}", a, request_id));
+        Ok(a)
+    }
+}
+
+fn main() {}
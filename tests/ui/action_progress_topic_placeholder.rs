@@ -0,0 +1,18 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct JobService;
+
+impl JobService {
+    #[action(progress_topic = "jobs/{id}/progress")]
+    async fn run(
+        &self,
+        job_id: String,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<String> {
+        ctx.debug(format!("Running {}", job_id));
+        Ok(job_id)
+    }
+}
+
+fn main() {}
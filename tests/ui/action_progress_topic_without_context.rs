@@ -0,0 +1,13 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct JobService;
+
+impl JobService {
+    #[action(progress_topic = "jobs/{job_id}/progress")]
+    async fn run(&self, job_id: String) -> anyhow::Result<String> {
+        Ok(job_id)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct ProxyService;
+
+impl ProxyService {
+    #[action(raw_payload)]
+    async fn forward(
+        &self,
+        target: String,
+        payload: runar_common::types::ArcValueType,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<runar_common::types::ArcValueType> {
+        ctx.debug(format!("Forwarding to {}", target));
+        Ok(payload)
+    }
+}
+
+fn main() {}
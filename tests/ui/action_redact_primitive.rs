@@ -0,0 +1,18 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct AccountService;
+
+impl AccountService {
+    #[action(redact = ["password"])]
+    async fn password(
+        &self,
+        username: String,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<String> {
+        ctx.debug(format!("Reading the password of {}", username));
+        Ok(username)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct PaymentService;
+
+impl PaymentService {
+    #[action]
+    async fn charge(
+        &self,
+        amount: f64,
+        #[request_id] id: u64,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<f64> {
+        ctx.debug(format!("Charging {} for request {}", amount, id));
+        Ok(amount)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct BlobService;
+
+impl BlobService {
+    #[action(raw_payload, serializer = "CompactSerde")]
+    async fn store(
+        &self,
+        payload: runar_common::types::ArcValueType,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<bool> {
+        ctx.debug("Storing a blob".to_string());
+        drop(payload);
+        Ok(true)
+    }
+}
+
+fn main() {}
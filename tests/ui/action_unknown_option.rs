@@ -0,0 +1,19 @@
+use runar_macros::action;
+
+#[derive(Clone)]
+struct MathService;
+
+impl MathService {
+    #[action(retries = 3)]
+    async fn add(
+        &self,
+        a: f64,
+        b: f64,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<f64> {
+        ctx.debug(format!("Adding {} + {}", a, b));
+        Ok(a + b)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use runar_macros::publish;
+
+#[publish(path = added)]
+async fn add(a: f64, b: f64) -> anyhow::Result<f64> {
+    Ok(a + b)
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use runar_macros::publish;
+
+#[publish(path = "orders", paths_from = |order: &u32| vec![format!("orders/{}", order)])]
+async fn place_order(
+    id: u32,
+    ctx: &runar_node::services::RequestContext,
+) -> anyhow::Result<u32> {
+    ctx.debug(format!("Placing order {}", id));
+    Ok(id)
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use runar_macros::service;
+
+#[derive(Clone)]
+struct MathService;
+
+#[service(name = "math", path = "math", action_defaults(path = "shared"))]
+impl MathService {
+    #[runar_macros::action]
+    async fn add(
+        &self,
+        a: f64,
+        b: f64,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<f64> {
+        ctx.debug(format!("Adding {} + {}", a, b));
+        Ok(a + b)
+    }
+}
+
+fn main() {}
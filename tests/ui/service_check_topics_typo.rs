@@ -0,0 +1,25 @@
+#![deny(deprecated)]
+
+use runar_macros::{action, publish, service, subscribe};
+use runar_node::services::{EventContext, RequestContext};
+
+#[derive(Clone)]
+struct OrderService;
+
+#[service(name = "Orders", path = "orders", check_topics = true)]
+impl OrderService {
+    #[publish(path = "placed")]
+    #[action]
+    async fn place(&self, order_id: String, ctx: &RequestContext) -> anyhow::Result<String> {
+        ctx.debug(format!("Placing {}", order_id));
+        Ok(order_id)
+    }
+
+    #[subscribe(path = "orders/plcaed")]
+    async fn on_placed(&self, order_id: String, ctx: &EventContext) -> anyhow::Result<()> {
+        ctx.debug(format!("Placed {}", order_id));
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use runar_macros::service;
+
+#[service(derive_clone = true)]
+#[derive(Debug, Clone)]
+pub struct CounterService {
+    step: u32,
+}
+
+fn main() {}
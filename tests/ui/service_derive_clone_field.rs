@@ -0,0 +1,11 @@
+use runar_macros::service;
+
+struct Connection;
+
+#[service(derive_clone = true)]
+pub struct CounterService {
+    connection: Connection,
+    step: u32,
+}
+
+fn main() {}
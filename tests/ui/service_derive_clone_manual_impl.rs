@@ -0,0 +1,15 @@
+use runar_macros::service;
+
+#[service(derive_clone = true)]
+pub struct CounterService {
+    step: u32,
+}
+
+// Not part of the macro input, so rustc reports the conflicting impls
+impl Clone for CounterService {
+    fn clone(&self) -> Self {
+        CounterService { step: self.step }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use runar_macros::service;
+
+#[derive(Clone)]
+struct UserService;
+
+#[service(name = "users", path = "users")]
+impl UserService {
+    #[runar_macros::action]
+    async fn get_user(
+        &self,
+        id: String,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<String> {
+        ctx.debug(format!("Getting user {}", id));
+        Ok(id)
+    }
+
+    #[runar_macros::action(name = "get-user")]
+    async fn fetch_user(
+        &self,
+        id: String,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<String> {
+        ctx.debug(format!("Fetching user {}", id));
+        Ok(id)
+    }
+}
+
+fn main() {}
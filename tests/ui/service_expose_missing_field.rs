@@ -0,0 +1,8 @@
+use runar_macros::service;
+
+#[service(expose = "storage")]
+struct CatalogService {
+    store: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+fn main() {}
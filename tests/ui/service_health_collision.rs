@@ -0,0 +1,15 @@
+use runar_macros::service;
+
+#[derive(Clone)]
+struct MathService;
+
+#[service(name = "math", path = "math", health = true)]
+impl MathService {
+    #[runar_macros::action]
+    async fn health(&self, ctx: &runar_node::services::RequestContext) -> anyhow::Result<String> {
+        ctx.debug("Checking health".to_string());
+        Ok("ok".to_string())
+    }
+}
+
+fn main() {}
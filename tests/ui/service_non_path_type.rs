@@ -0,0 +1,9 @@
+use runar_macros::service;
+
+#[derive(Clone)]
+struct CounterService;
+
+#[service(name = "counter", path = "counter")]
+impl (CounterService, CounterService) {}
+
+fn main() {}
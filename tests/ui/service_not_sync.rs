@@ -0,0 +1,18 @@
+use runar_macros::{action, service};
+use std::rc::Rc;
+
+#[derive(Clone)]
+struct CounterService {
+    count: Rc<u32>,
+}
+
+#[service(name = "counter", path = "counter")]
+impl CounterService {
+    #[action]
+    async fn count(&self, ctx: &runar_node::services::RequestContext) -> anyhow::Result<u32> {
+        ctx.debug("Reading count".to_string());
+        Ok(*self.count)
+    }
+}
+
+fn main() {}
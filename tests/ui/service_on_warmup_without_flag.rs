@@ -0,0 +1,22 @@
+use runar_macros::{action, service};
+use runar_node::services::{LifecycleContext, RequestContext};
+
+#[derive(Clone)]
+struct CatalogService;
+
+#[service(name = "Catalog", path = "catalog")]
+impl CatalogService {
+    #[on_warmup]
+    async fn load_prices(&self, ctx: &LifecycleContext) -> anyhow::Result<()> {
+        ctx.info("Loading the price cache".to_string());
+        Ok(())
+    }
+
+    #[action]
+    async fn price(&self, product: String, ctx: &RequestContext) -> anyhow::Result<f64> {
+        ctx.debug(format!("Looking up {}", product));
+        Ok(1.0)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use runar_macros::subscribe;
+
+#[derive(Clone)]
+struct OrderService;
+
+impl OrderService {
+    #[subscribe(path = "placed", dedup_store = "redis://cache:6379")]
+    async fn on_placed(
+        &self,
+        order_id: String,
+        ctx: &runar_node::services::EventContext,
+    ) -> anyhow::Result<()> {
+        ctx.debug(format!("Order {} placed", order_id));
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use runar_macros::subscribe;
+
+#[derive(Clone)]
+struct SensorService;
+
+impl SensorService {
+    #[subscribe(path = "readings")]
+    async fn on_reading(
+        &self,
+        reading: f64,
+        #[from_event_meta(timestamp)] ts: String,
+        ctx: &runar_node::services::EventContext,
+    ) -> anyhow::Result<()> {
+        ctx.debug(format!("Reading {} at {}", reading, ts));
+        Ok(())
+    }
+}
+
+fn main() {}
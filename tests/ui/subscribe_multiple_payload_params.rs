@@ -0,0 +1,19 @@
+use runar_macros::subscribe;
+
+#[derive(Clone)]
+struct GreeterService;
+
+impl GreeterService {
+    #[subscribe(path = "greeted")]
+    async fn on_greeted(
+        &self,
+        name: String,
+        greeting: String,
+        ctx: &runar_node::services::EventContext,
+    ) -> anyhow::Result<()> {
+        ctx.debug(format!("{} {}", greeting, name));
+        Ok(())
+    }
+}
+
+fn main() {}
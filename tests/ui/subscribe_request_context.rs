@@ -0,0 +1,18 @@
+use runar_macros::subscribe;
+
+#[derive(Clone)]
+struct GreeterService;
+
+impl GreeterService {
+    #[subscribe(path = "greeted")]
+    async fn on_greeted(
+        &self,
+        name: String,
+        ctx: &runar_node::services::RequestContext,
+    ) -> anyhow::Result<()> {
+        ctx.debug(format!("Greeted {}", name));
+        Ok(())
+    }
+}
+
+fn main() {}
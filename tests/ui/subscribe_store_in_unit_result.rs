@@ -0,0 +1,20 @@
+use runar_macros::subscribe;
+
+#[derive(Clone)]
+struct ThermostatService {
+    last_reading: std::sync::Arc<tokio::sync::RwLock<f64>>,
+}
+
+impl ThermostatService {
+    #[subscribe(path = "readings", store_in = "last_reading")]
+    async fn on_reading(
+        &self,
+        reading: f64,
+        ctx: &runar_node::services::EventContext,
+    ) -> anyhow::Result<()> {
+        ctx.debug(format!("Reading {}", reading));
+        Ok(())
+    }
+}
+
+fn main() {}
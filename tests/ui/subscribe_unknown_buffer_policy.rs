@@ -0,0 +1,18 @@
+use runar_macros::subscribe;
+
+#[derive(Clone)]
+struct SensorService;
+
+impl SensorService {
+    #[subscribe(path = "readings", max_concurrency = 1, buffer_full = "drop_newest")]
+    async fn on_reading(
+        &self,
+        reading: f64,
+        ctx: &runar_node::services::EventContext,
+    ) -> anyhow::Result<()> {
+        ctx.debug(format!("Reading {}", reading));
+        Ok(())
+    }
+}
+
+fn main() {}
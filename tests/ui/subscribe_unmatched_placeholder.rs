@@ -0,0 +1,18 @@
+use runar_macros::subscribe;
+
+#[derive(Clone)]
+struct SensorService;
+
+impl SensorService {
+    #[subscribe(path = "sensors/{id}/temp")]
+    async fn on_temp(
+        &self,
+        sensor: String,
+        ctx: &runar_node::services::EventContext,
+    ) -> anyhow::Result<()> {
+        ctx.debug(format!("Reading from {}", sensor));
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use runar_macros::subscribe;
+
+#[derive(Clone)]
+struct GreeterService;
+
+impl GreeterService {
+    #[subscribe(path = "greeted", via = "Greeting")]
+    async fn on_greeted(
+        &self,
+        name: String,
+        ctx: &runar_node::services::EventContext,
+    ) -> anyhow::Result<()> {
+        ctx.debug(format!("Greeted {}", name));
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,78 @@
+// Test for the verify_types service attribute
+//
+// The service macro generates a `registered_types_round_trip` test for the
+// types the service registers. Each of them must implement `Default`;
+// response-only types are left out and don't need to.
+//
+// A response-only type isn't registered by the service, yet a remote caller
+// still reads it back with a type of its own once it went over the wire.
+
+use anyhow::Result;
+use runar_common::types::ArcValueType;
+use runar_macros::{action, service, test_node};
+use runar_node::services::RequestContext;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Order {
+    pub item: String,
+    pub quantity: u32,
+    pub tags: Vec<String>,
+}
+
+// Only returned, so it isn't registered or round-tripped
+#[derive(Debug, Serialize)]
+pub struct Receipt {
+    pub item: String,
+    pub total: f64,
+}
+
+// The caller's view of a receipt, it only ever deserializes it
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct ReceiptView {
+    pub item: String,
+    pub total: f64,
+}
+
+#[derive(Clone)]
+pub struct OrderService;
+
+#[service(name = "Orders", path = "orders", verify_types = true)]
+impl OrderService {
+    #[action]
+    async fn place(&self, order: Order, ctx: &RequestContext) -> Result<Receipt> {
+        ctx.debug(format!("Placing an order for {}", order.item));
+        Ok(Receipt {
+            item: order.item,
+            total: order.quantity as f64 * 2.5,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_response_only_type_reaches_remote_caller() {
+    let node = test_node!(OrderService);
+    let order = Order {
+        item: "pen".to_string(),
+        quantity: 4,
+        tags: Vec::new(),
+    };
+    let response = node
+        .request("orders/place", Some(ArcValueType::from_struct(order)))
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Send the response over the wire as a remote node would
+    let serializer = node.serializer.read().await;
+    let bytes = serializer.serialize_value(&response).unwrap();
+    let mut received = serializer.deserialize_value(Arc::from(bytes)).unwrap();
+    assert_eq!(
+        received.as_type::<ReceiptView>().unwrap(),
+        ReceiptView {
+            item: "pen".to_string(),
+            total: 10.0,
+        }
+    );
+}
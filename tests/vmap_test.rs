@@ -0,0 +1,226 @@
+// Test for the vmap_arc and varr_arc macros
+//
+// This test builds ArcValueType maps and lists with the vmap_arc and varr_arc
+// macros and checks that primitive and nested values can be read back.
+
+use runar_common::types::ArcValueType;
+use runar_macros::{varr_arc, vmap_arc};
+
+#[test]
+fn test_vmap_arc_primitives() {
+    let name = "Runar".to_string();
+    let mut value = vmap_arc! {
+        "a" => 10.0,
+        "b" => 5,
+        "enabled" => true,
+        "label" => "sum",
+        "name" => name.clone(),
+    };
+
+    let map = value.as_map_ref::<String, ArcValueType>().unwrap();
+    assert_eq!(map.len(), 5);
+    assert_eq!(
+        map.get("a").unwrap().clone().as_type::<f64>().unwrap(),
+        10.0
+    );
+    assert_eq!(map.get("b").unwrap().clone().as_type::<i32>().unwrap(), 5);
+    assert!(map
+        .get("enabled")
+        .unwrap()
+        .clone()
+        .as_type::<bool>()
+        .unwrap());
+    assert_eq!(
+        map.get("label")
+            .unwrap()
+            .clone()
+            .as_type::<String>()
+            .unwrap(),
+        "sum"
+    );
+    assert_eq!(
+        map.get("name")
+            .unwrap()
+            .clone()
+            .as_type::<String>()
+            .unwrap(),
+        name
+    );
+}
+
+#[test]
+fn test_vmap_arc_nested_maps() {
+    let mut value = vmap_arc! {
+        "id" => 42,
+        "user" => {
+            "name" => "John Doe",
+            "age" => 30,
+            "address" => {
+                "city" => "Lisbon",
+                "zip" => 1000,
+            },
+        },
+    };
+
+    let map = value.as_map_ref::<String, ArcValueType>().unwrap();
+    assert_eq!(map.get("id").unwrap().clone().as_type::<i32>().unwrap(), 42);
+
+    let mut user = map.get("user").unwrap().clone();
+    let user = user.as_map_ref::<String, ArcValueType>().unwrap();
+    assert_eq!(
+        user.get("name")
+            .unwrap()
+            .clone()
+            .as_type::<String>()
+            .unwrap(),
+        "John Doe"
+    );
+    assert_eq!(
+        user.get("age").unwrap().clone().as_type::<i32>().unwrap(),
+        30
+    );
+
+    let mut address = user.get("address").unwrap().clone();
+    let address = address.as_map_ref::<String, ArcValueType>().unwrap();
+    assert_eq!(
+        address
+            .get("city")
+            .unwrap()
+            .clone()
+            .as_type::<String>()
+            .unwrap(),
+        "Lisbon"
+    );
+    assert_eq!(
+        address
+            .get("zip")
+            .unwrap()
+            .clone()
+            .as_type::<i32>()
+            .unwrap(),
+        1000
+    );
+}
+
+#[test]
+fn test_vmap_arc_empty() {
+    let mut value = vmap_arc! {};
+    let map = value.as_map_ref::<String, ArcValueType>().unwrap();
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_vmap_arc_list_values() {
+    let mut value = vmap_arc! {
+        "ids" => [1, 2],
+        "tags" => ["a", { "name" => "b" }],
+    };
+
+    let map = value.as_map_ref::<String, ArcValueType>().unwrap();
+
+    let mut ids = map.get("ids").unwrap().clone();
+    let ids = ids.as_list_ref::<ArcValueType>().unwrap();
+    assert_eq!(ids[0].clone().as_type::<i32>().unwrap(), 1);
+    assert_eq!(ids[1].clone().as_type::<i32>().unwrap(), 2);
+
+    let mut tags = map.get("tags").unwrap().clone();
+    let tags = tags.as_list_ref::<ArcValueType>().unwrap();
+    assert_eq!(tags[0].clone().as_type::<String>().unwrap(), "a");
+    let mut tag = tags[1].clone();
+    let tag = tag.as_map_ref::<String, ArcValueType>().unwrap();
+    assert_eq!(
+        tag.get("name")
+            .unwrap()
+            .clone()
+            .as_type::<String>()
+            .unwrap(),
+        "b"
+    );
+}
+
+#[test]
+fn test_vmap_arc_caller_variable_named_map() {
+    // A caller variable named `map` must not resolve to the map being built
+    let map = "caller".to_string();
+    let mut value = vmap_arc! {
+        "first" => 1,
+        "source" => map.clone(),
+        map.clone() => map.len() as i32,
+    };
+
+    let entries = value.as_map_ref::<String, ArcValueType>().unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(
+        entries
+            .get("source")
+            .unwrap()
+            .clone()
+            .as_type::<String>()
+            .unwrap(),
+        "caller"
+    );
+    assert_eq!(
+        entries
+            .get("caller")
+            .unwrap()
+            .clone()
+            .as_type::<i32>()
+            .unwrap(),
+        6
+    );
+}
+
+#[test]
+fn test_varr_arc_mixed_primitives() {
+    let name = "Runar".to_string();
+    let mut value = varr_arc![10.0, 5, true, "sum", name.clone()];
+
+    let list = value.as_list_ref::<ArcValueType>().unwrap();
+    assert_eq!(list.len(), 5);
+    assert_eq!(list[0].clone().as_type::<f64>().unwrap(), 10.0);
+    assert_eq!(list[1].clone().as_type::<i32>().unwrap(), 5);
+    assert!(list[2].clone().as_type::<bool>().unwrap());
+    assert_eq!(list[3].clone().as_type::<String>().unwrap(), "sum");
+    assert_eq!(list[4].clone().as_type::<String>().unwrap(), name);
+}
+
+#[test]
+fn test_varr_arc_nested() {
+    let mut value = varr_arc![
+        [1, 2],
+        {
+            "name" => "John Doe",
+            "age" => 30,
+        },
+    ];
+
+    let list = value.as_list_ref::<ArcValueType>().unwrap();
+    assert_eq!(list.len(), 2);
+
+    let mut numbers = list[0].clone();
+    let numbers = numbers.as_list_ref::<ArcValueType>().unwrap();
+    assert_eq!(numbers[0].clone().as_type::<i32>().unwrap(), 1);
+    assert_eq!(numbers[1].clone().as_type::<i32>().unwrap(), 2);
+
+    let mut user = list[1].clone();
+    let user = user.as_map_ref::<String, ArcValueType>().unwrap();
+    assert_eq!(
+        user.get("name")
+            .unwrap()
+            .clone()
+            .as_type::<String>()
+            .unwrap(),
+        "John Doe"
+    );
+    assert_eq!(
+        user.get("age").unwrap().clone().as_type::<i32>().unwrap(),
+        30
+    );
+}
+
+#[test]
+fn test_varr_arc_empty() {
+    let mut value = varr_arc![];
+    let list = value.as_list_ref::<ArcValueType>().unwrap();
+    assert!(list.is_empty());
+}
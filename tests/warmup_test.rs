@@ -0,0 +1,84 @@
+// Test for the warmup service attribute
+//
+// The #[on_warmup] hooks run when the node starts the service, so the
+// cache they fill is ready for the first request.
+
+use anyhow::Result;
+use futures::lock::Mutex;
+use runar_macros::{action, service};
+use runar_node::services::{LifecycleContext, RequestContext};
+use std::{collections::HashMap, sync::Arc};
+
+#[derive(Clone)]
+pub struct CatalogService {
+    prices: Arc<Mutex<HashMap<String, f64>>>,
+    warmups: Arc<Mutex<Vec<&'static str>>>,
+}
+
+#[service(name = "Catalog", path = "catalog", warmup = true)]
+impl CatalogService {
+    #[on_warmup]
+    async fn load_prices(&self, ctx: &LifecycleContext) -> Result<()> {
+        ctx.info("Loading the price cache".to_string());
+        let mut prices = self.prices.lock().await;
+        prices.insert("apple".to_string(), 1.5);
+        prices.insert("pear".to_string(), 2.0);
+        self.warmups.lock().await.push("load_prices");
+        Ok(())
+    }
+
+    #[on_warmup]
+    async fn check_prices(&self, ctx: &LifecycleContext) -> Result<()> {
+        let count = self.prices.lock().await.len();
+        ctx.info(format!("Price cache holds {} entries", count));
+        self.warmups.lock().await.push("check_prices");
+        Ok(())
+    }
+
+    #[action]
+    async fn price(&self, product: String, ctx: &RequestContext) -> Result<f64> {
+        ctx.debug(format!("Looking up the price of {}", product));
+        self.prices
+            .lock()
+            .await
+            .get(&product)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Unknown product {}", product))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runar_common::types::ArcValueType;
+    use runar_node::Node;
+    use runar_node::NodeConfig;
+
+    #[tokio::test]
+    async fn test_warmup_runs_on_start() {
+        let mut config = NodeConfig::new("test-node", "test_network");
+        config.network_config = None;
+        let mut node = Node::new(config).await.unwrap();
+
+        let warmups = Arc::new(Mutex::new(Vec::new()));
+        let service = CatalogService {
+            prices: Arc::new(Mutex::new(HashMap::new())),
+            warmups: warmups.clone(),
+        };
+        node.add_service(service).await.unwrap();
+        node.start().await.unwrap();
+
+        // The hooks ran once each, in declaration order
+        assert_eq!(*warmups.lock().await, vec!["load_prices", "check_prices"]);
+
+        let mut price = node
+            .request(
+                "catalog/price",
+                Some(ArcValueType::new_primitive("pear".to_string())),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(price.as_type::<f64>().unwrap(), 2.0);
+    }
+}